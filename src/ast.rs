@@ -0,0 +1,754 @@
+//! Untyped WAVE syntax trees.
+//!
+//! A [`Node`] can be parsed from WAVE-encoded input without knowing the type
+//! of the encoded value, following the grammar in `wave_ebnf.md`. A node can
+//! then be type-checked and converted into a [`WasmValue`] with
+//! [`Node::to_wasm_value`].
+
+use std::borrow::Cow;
+
+use indexmap::IndexSet;
+
+use crate::{
+    fmt::DisplayType,
+    lex::Span,
+    parser::{
+        self, flattenable, Location, ParseNumber, Parser, ParserError, ParserErrorKind, Recovery,
+    },
+    WasmType, WasmTypeKind, WasmValue,
+};
+
+/// Parses the given WAVE-encoded input into an untyped [`Node`].
+/// ```
+/// use wasm_wave::{ast::NodeKind, value::{Type, Value}};
+/// # fn main() -> Result<(), wasm_wave::parser::ParserError> {
+/// let node = wasm_wave::ast::parse("[1, 2, 3]")?;
+/// assert!(matches!(node.kind(), NodeKind::List(elements) if elements.len() == 3));
+/// let val: Value = node.to_wasm_value(&Type::list(Type::U8))?;
+/// assert_eq!(val, Value::from([1u8, 2, 3]));
+/// # Ok(())
+/// # }
+/// ```
+pub fn parse(input: &str) -> Result<Node, ParserError> {
+    let mut parser = Parser::new(input);
+    let node = parser.parse_node()?;
+    parser.finish()?;
+    Ok(node)
+}
+
+/// A node in an untyped WAVE syntax tree.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    kind: NodeKind,
+    span: Span,
+    location: Location,
+}
+
+/// The kind of a [`Node`].
+///
+/// Bool, variant, enum, option and result values are all represented as
+/// [`NodeKind::VariantCase`]s because they can't be distinguished without
+/// type information.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum NodeKind {
+    /// A number, e.g. `123`, `-1.5e10` or `-inf`, as written in the input.
+    /// Note that `inf` and `nan` are labels.
+    Number(Box<str>),
+    /// A char, e.g. `'x'`
+    Char(char),
+    /// A string, e.g. `"abc"`
+    String(Box<str>),
+    /// A label with an optional payload, e.g. `true`, `none` or `some(1)`
+    VariantCase {
+        /// The case label
+        label: Label,
+        /// The case payload, if any
+        payload: Option<Box<Node>>,
+    },
+    /// A tuple, e.g. `(1, "two")`
+    Tuple(Vec<Node>),
+    /// A list, e.g. `[1, 2, 3]`
+    List(Vec<Node>),
+    /// Flags, e.g. `{read, write}`; also an empty `{}`
+    Flags(Vec<Label>),
+    /// A record, e.g. `{field-a: 1, field-b: "two"}`
+    Record(Vec<(Label, Node)>),
+    /// A value that failed to parse, standing in for it in a recovering
+    /// parse (see [`Parser::parse_value_recovering`]). Its error has already
+    /// been reported.
+    Error,
+}
+
+/// A label, used for variant cases, record fields and flags.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    name: Box<str>,
+    span: Span,
+    location: Location,
+}
+
+impl Node {
+    pub(crate) fn new(kind: NodeKind, span: Span, location: Location) -> Self {
+        Self {
+            kind,
+            span,
+            location,
+        }
+    }
+
+    /// Returns the kind of this node.
+    pub fn kind(&self) -> &NodeKind {
+        &self.kind
+    }
+
+    /// Returns the byte span of the input this node was parsed from.
+    pub fn span(&self) -> Span {
+        self.span.clone()
+    }
+
+    /// Returns the line and column of the start of this node.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Type-checks this node against the given [`WasmType`] and converts it
+    /// into a corresponding [`WasmValue`].
+    pub fn to_wasm_value<V: WasmValue>(&self, ty: &V::Type) -> Result<V, ParserError> {
+        self.lower(ty, None)
+    }
+
+    // Like `to_wasm_value`, but records errors in `recovery` and leaves out
+    // parts of the value with errors where possible.
+    pub(crate) fn lower_recovering<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        recovery: &mut Recovery,
+    ) -> Option<V> {
+        let res = self.lower(ty, Some(recovery));
+        recover(res, Some(recovery)).ok().flatten()
+    }
+
+    fn lower<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        if let NodeKind::Error = self.kind {
+            // The error was recorded when parsing
+            let err = self.type_mismatch(ty);
+            return Err(match rec {
+                Some(rec) => rec.recorded(err),
+                None => err,
+            });
+        }
+        Ok(match ty.kind() {
+            WasmTypeKind::Bool => V::make_bool(self.to_bool(ty)?),
+            WasmTypeKind::S8 => V::make_s8(self.to_number(ty)?),
+            WasmTypeKind::S16 => V::make_s16(self.to_number(ty)?),
+            WasmTypeKind::S32 => V::make_s32(self.to_number(ty)?),
+            WasmTypeKind::S64 => V::make_s64(self.to_number(ty)?),
+            WasmTypeKind::U8 => V::make_u8(self.to_number(ty)?),
+            WasmTypeKind::U16 => V::make_u16(self.to_number(ty)?),
+            WasmTypeKind::U32 => V::make_u32(self.to_number(ty)?),
+            WasmTypeKind::U64 => V::make_u64(self.to_number(ty)?),
+            WasmTypeKind::Float32 => V::make_float32(self.to_number(ty)?),
+            WasmTypeKind::Float64 => V::make_float64(self.to_number(ty)?),
+            WasmTypeKind::Char => match &self.kind {
+                NodeKind::Char(ch) => V::make_char(*ch),
+                _ => return Err(self.type_mismatch(ty)),
+            },
+            WasmTypeKind::String => match &self.kind {
+                NodeKind::String(s) => V::make_string(Cow::Borrowed(s)),
+                _ => return Err(self.type_mismatch(ty)),
+            },
+            WasmTypeKind::List => self.to_list(ty, rec)?,
+            WasmTypeKind::Record => self.to_record(ty, rec)?,
+            WasmTypeKind::Tuple => self.to_tuple(ty, rec)?,
+            WasmTypeKind::Variant => self.to_variant(ty, rec)?,
+            WasmTypeKind::Enum => self.to_enum(ty, rec)?,
+            WasmTypeKind::Option => self.to_option(ty, rec)?,
+            WasmTypeKind::Result => self.to_result(ty, rec)?,
+            WasmTypeKind::Flags => self.to_flags(ty, rec)?,
+            WasmTypeKind::Own
+            | WasmTypeKind::Borrow
+            | WasmTypeKind::Stream
+            | WasmTypeKind::Future
+            | WasmTypeKind::ErrorContext => self.to_handle(ty)?,
+            WasmTypeKind::Unsupported => {
+                return Err(self.error(ParserErrorKind::Unsupported("unsupported type".into())))
+            }
+        })
+    }
+
+    fn to_bool(&self, ty: &impl WasmType) -> Result<bool, ParserError> {
+        match self.case_without_payload() {
+            Some(parser::TRUE) => Ok(true),
+            Some(parser::FALSE) => Ok(false),
+            _ => Err(self.type_mismatch(ty)),
+        }
+    }
+
+    fn to_number<T: ParseNumber>(&self, ty: &impl WasmType) -> Result<T, ParserError> {
+        let number = match (&self.kind, ty.kind()) {
+            (NodeKind::Number(number), _) => number.as_ref(),
+            // `inf` and `nan` are parsed as labels
+            (_, WasmTypeKind::Float32 | WasmTypeKind::Float64) => match self.case_without_payload()
+            {
+                Some(name @ ("inf" | "nan")) => name,
+                _ => return Err(self.type_mismatch(ty)),
+            },
+            _ => return Err(self.type_mismatch(ty)),
+        };
+        T::parse_number(number).map_err(|kind| self.error(kind))
+    }
+
+    fn to_list<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        mut rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        let NodeKind::List(nodes) = &self.kind else {
+            return Err(self.type_mismatch(ty));
+        };
+        let element_type = ty.list_element_type().unwrap();
+        let mut elements = Vec::with_capacity(nodes.len());
+        for (idx, node) in nodes.iter().enumerate() {
+            ParserErrorKind::check_list_capacity(ty, idx).map_err(|kind| node.error(kind))?;
+            let res = node.lower(&element_type, rec.as_deref_mut());
+            elements.extend(recover(res, rec.as_deref_mut())?);
+        }
+        ParserErrorKind::check_list_length(ty, elements.len()).map_err(|kind| self.error(kind))?;
+        V::make_list(ty, elements).map_err(|err| self.make_value_error(err))
+    }
+
+    fn to_record<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        mut rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        let fields = match &self.kind {
+            NodeKind::Record(fields) => fields.as_slice(),
+            // An empty `{}` is a valid record if all fields are optional.
+            NodeKind::Flags(flags) if flags.is_empty() => &[],
+            _ => return Err(self.type_mismatch(ty)),
+        };
+        let field_types = ty.record_fields().collect::<Vec<_>>();
+        let mut values = field_types.iter().map(|_| None).collect::<Vec<_>>();
+        let mut seen = vec![false; field_types.len()];
+        for (label, node) in fields {
+            let Some(idx) = field_types
+                .iter()
+                .position(|(name, _)| name.as_ref() == label.name())
+                .filter(|idx| !seen[*idx])
+            else {
+                let remaining = field_types
+                    .iter()
+                    .zip(&seen)
+                    .filter(|(_, seen)| !**seen)
+                    .map(|((name, _), _)| name.as_ref());
+                recover::<()>(Err(label.unexpected_name(remaining)), rec.as_deref_mut())?;
+                continue;
+            };
+            seen[idx] = true;
+            let res = node.lower(&field_types[idx].1, rec.as_deref_mut());
+            values[idx] = recover(res, rec.as_deref_mut())?;
+        }
+
+        let mut fields = Vec::with_capacity(field_types.len());
+        for ((name, ty), (maybe_val, seen)) in field_types.iter().zip(values.into_iter().zip(seen))
+        {
+            let val = match maybe_val {
+                Some(val) => val,
+                None if ty.kind() == WasmTypeKind::Option => {
+                    // Omitted `option` field (or one with errors); use "none"
+                    V::make_option(ty, None).map_err(|err| self.make_value_error(err))?
+                }
+                None => {
+                    let err = self.error(ParserErrorKind::FieldMissing(name.to_string()));
+                    return Err(match rec {
+                        // The field's error was already recorded
+                        Some(rec) if seen => rec.recorded(err),
+                        _ => err,
+                    });
+                }
+            };
+            fields.push((name.as_ref(), val));
+        }
+        V::make_record(ty, fields).map_err(|err| self.make_value_error(err))
+    }
+
+    fn to_tuple<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        mut rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        let types = ty.tuple_element_types().collect::<Vec<_>>();
+        let NodeKind::Tuple(nodes) = &self.kind else {
+            return Err(self.type_mismatch(ty));
+        };
+        if nodes.len() != types.len() {
+            return Err(self.type_mismatch(ty));
+        }
+        let mut values = Vec::with_capacity(nodes.len());
+        for (node, ty) in nodes.iter().zip(&types) {
+            let res = node.lower(ty, rec.as_deref_mut());
+            values.extend(recover(res, rec.as_deref_mut())?);
+        }
+        if values.len() != types.len() {
+            // Element errors were already recorded
+            let err = self.type_mismatch(ty);
+            return match rec {
+                Some(rec) => Err(rec.recorded(err)),
+                None => Err(err),
+            };
+        }
+        V::make_tuple(ty, values).map_err(|err| self.make_value_error(err))
+    }
+
+    fn to_variant<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        let NodeKind::VariantCase { label, payload } = &self.kind else {
+            return Err(self.type_mismatch(ty));
+        };
+        let Some((case_name, case_ty)) = ty
+            .variant_cases()
+            .find(|(name, _)| name.as_ref() == label.name())
+        else {
+            return Err(label.unexpected_name(ty.variant_cases().map(|(name, _)| name)));
+        };
+        let payload = self.to_payload(case_ty.as_ref(), payload.as_deref(), rec)?;
+        V::make_variant(ty, case_name.as_ref(), payload).map_err(|err| self.make_value_error(err))
+    }
+
+    fn to_enum<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        let NodeKind::VariantCase { label, payload } = &self.kind else {
+            return Err(self.type_mismatch(ty));
+        };
+        if !ty.enum_cases().any(|name| name.as_ref() == label.name()) {
+            return Err(label.unexpected_name(ty.enum_cases()));
+        }
+        self.to_payload::<V>(None, payload.as_deref(), rec)?;
+        V::make_enum(ty, label.name()).map_err(|err| self.make_value_error(err))
+    }
+
+    fn to_handle<V: WasmValue>(&self, ty: &V::Type) -> Result<V, ParserError> {
+        let NodeKind::VariantCase {
+            label,
+            payload: Some(payload),
+        } = &self.kind
+        else {
+            return Err(self.type_mismatch(ty));
+        };
+        let handle_label = crate::ty::handle_label(ty);
+        if label.name() != handle_label {
+            return Err(label.unexpected_name([handle_label]));
+        }
+        let NodeKind::Number(number) = &payload.kind else {
+            return Err(self.type_mismatch(ty));
+        };
+        let id = u32::parse_number(number).map_err(|kind| payload.error(kind))?;
+        V::make_handle(ty, id).map_err(|err| self.make_value_error(err))
+    }
+
+    fn to_option<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        let some_ty = ty.option_some_type().unwrap();
+        let val = match &self.kind {
+            NodeKind::VariantCase { label, payload } if label.name() == parser::SOME => {
+                self.to_payload(Some(&some_ty), payload.as_deref(), rec)?
+            }
+            NodeKind::VariantCase { label, payload } if label.name() == parser::NONE => {
+                self.to_payload::<V>(None, payload.as_deref(), rec)?;
+                None
+            }
+            _ if flattenable(some_ty.kind()) => Some(self.lower(&some_ty, rec)?),
+            NodeKind::VariantCase { label, .. } => {
+                return Err(label.unexpected_name([parser::SOME, parser::NONE]))
+            }
+            _ => return Err(self.type_mismatch(ty)),
+        };
+        V::make_option(ty, val).map_err(|err| self.make_value_error(err))
+    }
+
+    fn to_result<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        let (ok_ty, err_ty) = ty.result_types().unwrap();
+        let val = match &self.kind {
+            NodeKind::VariantCase { label, payload } if label.name() == parser::OK => {
+                Ok(self.to_payload(ok_ty.as_ref(), payload.as_deref(), rec)?)
+            }
+            NodeKind::VariantCase { label, payload } if label.name() == parser::ERR => {
+                Err(self.to_payload(err_ty.as_ref(), payload.as_deref(), rec)?)
+            }
+            _ if ok_ty.as_ref().is_some_and(|ty| flattenable(ty.kind())) => {
+                Ok(Some(self.lower(ok_ty.as_ref().unwrap(), rec)?))
+            }
+            NodeKind::VariantCase { label, .. } => {
+                return Err(label.unexpected_name([parser::OK, parser::ERR]))
+            }
+            _ => return Err(self.type_mismatch(ty)),
+        };
+        V::make_result(ty, val).map_err(|err| self.make_value_error(err))
+    }
+
+    fn to_flags<V: WasmValue>(
+        &self,
+        ty: &V::Type,
+        mut rec: Option<&mut Recovery>,
+    ) -> Result<V, ParserError> {
+        let NodeKind::Flags(labels) = &self.kind else {
+            return Err(self.type_mismatch(ty));
+        };
+        let mut seen = IndexSet::new();
+        for label in labels {
+            if !ty.flags_names().any(|name| name.as_ref() == label.name())
+                || seen.contains(label.name())
+            {
+                let remaining = ty
+                    .flags_names()
+                    .filter(|name| !seen.contains(name.as_ref()))
+                    .collect::<Vec<_>>();
+                recover::<()>(Err(label.unexpected_name(remaining)), rec.as_deref_mut())?;
+                continue;
+            }
+            seen.insert(label.name());
+        }
+        V::make_flags(ty, seen).map_err(|err| self.make_value_error(err))
+    }
+
+    // Converts a variant case payload, checking its presence against `ty`.
+    fn to_payload<V: WasmValue>(
+        &self,
+        ty: Option<&V::Type>,
+        payload: Option<&Node>,
+        rec: Option<&mut Recovery>,
+    ) -> Result<Option<V>, ParserError> {
+        match (ty, payload) {
+            (Some(ty), Some(node)) => Ok(Some(node.lower(ty, rec)?)),
+            (None, None) => Ok(None),
+            (Some(ty), None) => Err(self.error(ParserErrorKind::InvalidPayload(format!(
+                "expected {:#} payload",
+                DisplayType(ty.clone())
+            )))),
+            (None, Some(node)) => Err(node.error(ParserErrorKind::InvalidPayload(
+                "expected no payload".into(),
+            ))),
+        }
+    }
+
+    // Returns the label name if this is a variant case without a payload.
+    fn case_without_payload(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::VariantCase {
+                label,
+                payload: None,
+            } => Some(label.name()),
+            _ => None,
+        }
+    }
+
+    fn describe(&self) -> String {
+        match &self.kind {
+            NodeKind::Number(number) => format!("number `{number}`"),
+            NodeKind::Char(_) => "char".into(),
+            NodeKind::String(_) => "string".into(),
+            NodeKind::VariantCase {
+                label,
+                payload: None,
+            } => format!("label `{}`", label.name()),
+            NodeKind::VariantCase { label, .. } => format!("`{}(...)`", label.name()),
+            NodeKind::Tuple(nodes) => format!("tuple of {} values", nodes.len()),
+            NodeKind::List(_) => "list".into(),
+            NodeKind::Flags(_) => "flags".into(),
+            NodeKind::Record(_) => "record".into(),
+            NodeKind::Error => "invalid value".into(),
+        }
+    }
+
+    fn type_mismatch(&self, ty: &impl WasmType) -> ParserError {
+        self.error(ParserErrorKind::TypeMismatch {
+            expected: format!("{:#}", DisplayType(ty.clone())),
+            got: self.describe(),
+        })
+    }
+
+    fn make_value_error(&self, err: impl std::fmt::Display) -> ParserError {
+        self.error(ParserErrorKind::MakeValueError(err.to_string()))
+    }
+
+    fn error(&self, kind: ParserErrorKind) -> ParserError {
+        ParserError::new(kind, self.span(), self.location)
+    }
+}
+
+// Records an error in recovery mode, returning None; otherwise returns it.
+fn recover<T>(
+    res: Result<T, ParserError>,
+    rec: Option<&mut Recovery>,
+) -> Result<Option<T>, ParserError> {
+    match (res, rec) {
+        (Ok(val), _) => Ok(Some(val)),
+        (Err(err), Some(rec)) => {
+            rec.record(err);
+            Ok(None)
+        }
+        (Err(err), None) => Err(err),
+    }
+}
+
+impl Label {
+    pub(crate) fn new(name: impl Into<Box<str>>, span: Span, location: Location) -> Self {
+        Self {
+            name: name.into(),
+            span,
+            location,
+        }
+    }
+
+    /// Returns the label name, without any `%` prefix.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the byte span of the input this label was parsed from.
+    pub fn span(&self) -> Span {
+        self.span.clone()
+    }
+
+    /// Returns the line and column of the start of this label.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    fn unexpected_name<T: Into<String>>(
+        &self,
+        expected: impl IntoIterator<Item = T>,
+    ) -> ParserError {
+        ParserError::new(
+            ParserErrorKind::UnexpectedName {
+                expected: expected.into_iter().map(Into::into).collect(),
+                got: self.name.to_string(),
+            },
+            self.span(),
+            self.location,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::value::{Type, Value};
+
+    use super::*;
+
+    #[test]
+    fn parse_node_kinds() {
+        for (input, expected) in [
+            ("123", "Number(123)"),
+            ("-1.5e10", "Number(-1.5e10)"),
+            ("-inf", "Number(-inf)"),
+            ("inf", "VariantCase(inf)"),
+            ("'☃'", "Char(☃)"),
+            (r#""a\nb""#, "String(a\nb)"),
+            ("%true", "VariantCase(true)"),
+            ("some(none)", "VariantCase(some, VariantCase(none))"),
+            ("(1, 'x',)", "Tuple(Number(1), Char(x))"),
+            ("[]", "List()"),
+            ("[[1], []]", "List(List(Number(1)), List())"),
+            ("{}", "Flags()"),
+            ("{read, write,}", "Flags(read, write)"),
+            ("{a: 1, b: {c}}", "Record(a: Number(1), b: Flags(c))"),
+            ("[1, // one\n /* two */ 2]", "List(Number(1), Number(2))"),
+        ] {
+            let node = parse(input).unwrap_or_else(|err| panic!("error parsing {input:?}: {err}"));
+            assert_eq!(summarize(&node), expected, "for {input:?}");
+        }
+    }
+
+    #[test]
+    fn parse_node_errors() {
+        for input in [
+            "",
+            "()",
+            "(,)",
+            "[,]",
+            "[1,,]",
+            "{a}x",
+            "{a: 1, b}",
+            "{a, b: 1}",
+            "1.2.3",
+            "-nan",
+            "some(1",
+            "x y",
+        ] {
+            parse(input).expect_err(input);
+        }
+    }
+
+    #[test]
+    fn node_spans() {
+        let node = parse(" [\n  {a: 1},\n  some(2),\n]").unwrap();
+        assert_eq!(node.span(), 1..25);
+        let NodeKind::List(elements) = node.kind() else {
+            panic!("not a list: {node:?}");
+        };
+        assert_eq!(elements[0].span(), 5..11);
+        assert_eq!(elements[1].span(), 15..22);
+        assert_eq!(elements[1].location(), Location { line: 3, column: 3 });
+        let NodeKind::Record(fields) = elements[0].kind() else {
+            panic!("not a record: {:?}", elements[0]);
+        };
+        assert_eq!(fields[0].0.span(), 6..7);
+        assert_eq!(fields[0].1.location(), Location { line: 2, column: 7 });
+    }
+
+    #[test]
+    fn to_wasm_value_matches_parser() {
+        let record_ty = Type::record([("a", Type::U8), ("b", Type::option(Type::CHAR))]).unwrap();
+        let variant_ty = Type::variant([("off", None), ("on", Some(Type::U8))]).unwrap();
+        for (ty, input) in [
+            (Type::BOOL, "true"),
+            (Type::S8, "-128"),
+            (Type::U64, "18446744073709551615"),
+            (Type::U32, "0xdead_beef"),
+            (Type::S8, "-0b1000_0000"),
+            (Type::FLOAT32, "-1.5"),
+            (Type::FLOAT64, "inf"),
+            (Type::FLOAT64, "-inf"),
+            (Type::CHAR, r"'\u{1F44B}'"),
+            (Type::STRING, r#""a\tb""#),
+            (Type::list(Type::U8), "[1, 2, 3]"),
+            (Type::tuple([Type::U8, Type::STRING]).unwrap(), "(1, \"x\")"),
+            (record_ty.clone(), "{b: 'x', a: 1}"),
+            (record_ty, "{a: 1}"),
+            (Type::record([("c", Type::option(Type::U8))]).unwrap(), "{}"),
+            (variant_ty.clone(), "off"),
+            (variant_ty, "on(1)"),
+            (Type::enum_ty(["north", "south"]).unwrap(), "south"),
+            (Type::option(Type::U8), "some(1)"),
+            (Type::option(Type::U8), "1"),
+            (Type::option(Type::U8), "none"),
+            (Type::option(Type::option(Type::U8)), "some(none)"),
+            (Type::result(Some(Type::U8), None), "1"),
+            (Type::result(Some(Type::U8), None), "err"),
+            (Type::result(None, Some(Type::STRING)), "err(\"oops\")"),
+            (Type::flags(["read", "write"]).unwrap(), "{write, read}"),
+            (Type::flags(["read", "write"]).unwrap(), "{}"),
+        ] {
+            let expected: Value = crate::from_str(&ty, input).unwrap();
+            let got: Value = parse(input)
+                .unwrap()
+                .to_wasm_value(&ty)
+                .unwrap_or_else(|err| panic!("error converting {input:?}: {err}"));
+            assert_eq!(got, expected, "for {input:?}");
+        }
+
+        let nan: Value = parse("nan").unwrap().to_wasm_value(&Type::FLOAT32).unwrap();
+        assert!(nan.unwrap_float32().is_nan());
+    }
+
+    #[test]
+    fn to_wasm_value_errors() {
+        let record_ty = Type::record([("a", Type::U8), ("b", Type::option(Type::CHAR))]).unwrap();
+        let variant_ty = Type::variant([("off", None), ("on", Some(Type::U8))]).unwrap();
+        for (ty, input, span) in [
+            (Type::BOOL, "maybe", 0..5),
+            (Type::U8, "256", 0..3),
+            (Type::U8, "-1", 0..2),
+            (Type::U8, "1.5", 0..3),
+            (Type::U8, "\"1\"", 0..3),
+            (Type::FLOAT32, "infinity", 0..8),
+            (Type::list(Type::U8), "[1, x]", 4..5),
+            (Type::tuple([Type::U8]).unwrap(), "(1, 2)", 0..6),
+            (record_ty.clone(), "{b: 'x'}", 0..8),
+            (record_ty.clone(), "{a: 1, c: 2}", 7..8),
+            (record_ty.clone(), "{a: 1, a: 2}", 7..8),
+            (record_ty, "{}", 0..2),
+            (variant_ty.clone(), "on", 0..2),
+            (variant_ty.clone(), "off(1)", 4..5),
+            (variant_ty, "other", 0..5),
+            (Type::enum_ty(["north"]).unwrap(), "north(1)", 6..7),
+            (Type::option(Type::U8), "some", 0..4),
+            (Type::option(Type::option(Type::U8)), "1", 0..1),
+            (Type::result(None, None), "ok(1)", 3..4),
+            (Type::flags(["read"]).unwrap(), "{read, read}", 7..11),
+            (Type::flags(["read"]).unwrap(), "{a: 1}", 0..6),
+        ] {
+            let err = parse(input)
+                .unwrap()
+                .to_wasm_value::<Value>(&ty)
+                .expect_err(input);
+            assert_eq!(err.span(), span, "for {input:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn named_type_errors() {
+        let profile = Type::record([("id", Type::U32)])
+            .unwrap()
+            .with_name("user-profile", None);
+        let err = parse("[1]")
+            .unwrap()
+            .to_wasm_value::<Value>(&profile)
+            .unwrap_err();
+        assert!(
+            matches!(err.kind(), ParserErrorKind::TypeMismatch { expected, .. } if expected == "user-profile"),
+            "{err}"
+        );
+
+        let variant_ty = Type::variant([("set", Some(profile))]).unwrap();
+        let err = parse("set")
+            .unwrap()
+            .to_wasm_value::<Value>(&variant_ty)
+            .unwrap_err();
+        assert!(
+            matches!(err.kind(), ParserErrorKind::InvalidPayload(msg) if msg == "expected user-profile payload"),
+            "{err}"
+        );
+    }
+
+    fn summarize(node: &Node) -> String {
+        let join = |nodes: &[Node]| nodes.iter().map(summarize).collect::<Vec<_>>().join(", ");
+        match node.kind() {
+            NodeKind::Number(number) => format!("Number({number})"),
+            NodeKind::Char(ch) => format!("Char({ch})"),
+            NodeKind::String(s) => format!("String({s})"),
+            NodeKind::VariantCase {
+                label,
+                payload: None,
+            } => format!("VariantCase({})", label.name()),
+            NodeKind::VariantCase {
+                label,
+                payload: Some(payload),
+            } => format!("VariantCase({}, {})", label.name(), summarize(payload)),
+            NodeKind::Tuple(nodes) => format!("Tuple({})", join(nodes)),
+            NodeKind::List(nodes) => format!("List({})", join(nodes)),
+            NodeKind::Flags(labels) => {
+                let names = labels.iter().map(Label::name).collect::<Vec<_>>();
+                format!("Flags({})", names.join(", "))
+            }
+            NodeKind::Error => "Error".into(),
+            NodeKind::Record(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(label, node)| format!("{}: {}", label.name(), summarize(node)))
+                    .collect::<Vec<_>>();
+                format!("Record({})", fields.join(", "))
+            }
+        }
+    }
+}
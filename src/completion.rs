@@ -1,6 +1,6 @@
 use crate::{
-    lex::Token,
-    parser::{Parser, ParserError},
+    lex::{Token, Tokenizer},
+    parser::{Parser, ParserErrorKind},
     value::{Type, Value},
     WasmType,
 };
@@ -13,8 +13,11 @@ pub fn completions(ty: &impl WasmType, input: &str) -> Option<Completions> {
     let ty = Type::from_wasm_type(ty)?;
     let mut parser = Parser::new(input);
     parser.completion(true);
-    match parser.parse_value::<Value>(&ty) {
-        Err(ParserError::UnexpectedEnd { completions, .. }) => completions,
+    match parser
+        .parse_value::<Value>(&ty)
+        .map_err(|err| err.into_kind())
+    {
+        Err(ParserErrorKind::UnexpectedEnd { completions, .. }) => completions,
         _ => None,
     }
 }
@@ -33,8 +36,11 @@ pub fn params_completions<T: WasmType>(
         .collect::<Option<Vec<_>>>()?;
     let mut parser = Parser::new(input);
     parser.completion(true);
-    match parser.parse_params::<Value>(types.iter()) {
-        Err(ParserError::UnexpectedEnd { completions, .. }) => completions,
+    match parser
+        .parse_params::<Value>(types.iter())
+        .map_err(|err| err.into_kind())
+    {
+        Err(ParserErrorKind::UnexpectedEnd { completions, .. }) => completions,
         _ => None,
     }
 }
@@ -48,15 +54,27 @@ pub struct Completions {
 }
 
 impl Completions {
-    pub(crate) fn new(partial: &str, err: &ParserError, ty: Option<&impl WasmType>) -> Self {
-        use ParserError::*;
-        let candidates = match err {
-            UnexpectedName { expected, got } => replacement_candidates(got, expected),
-            UnexpectedToken {
-                expected,
-                got: None,
-            } => token_candidates(expected),
-            _ => {
+    // Returns completions for an error at the end of `input`, while parsing a
+    // value that starts at `start`.
+    pub(crate) fn new(
+        input: &str,
+        start: usize,
+        err: &ParserErrorKind,
+        ty: Option<&impl WasmType>,
+    ) -> Self {
+        use ParserErrorKind::*;
+        let partial = &input[start..];
+        let candidates = match (comment_candidate(input), err) {
+            (Some(candidate), _) => vec![candidate.into()],
+            (None, UnexpectedName { expected, got }) => replacement_candidates(got, expected),
+            (
+                None,
+                UnexpectedToken {
+                    expected,
+                    got: None,
+                },
+            ) => token_candidates(expected),
+            (None, _) => {
                 if let Some(ty) = ty {
                     type_candidates(partial, ty)
                 } else {
@@ -81,8 +99,39 @@ impl Completions {
     }
 }
 
+// Returns the text that closes a comment left open at the end of `input`, if
+// any; other candidates would be swallowed by the comment.
+fn comment_candidate(input: &str) -> Option<&'static str> {
+    let mut tokens = Tokenizer::new(input);
+    let mut last = None;
+    loop {
+        let start = tokens.pos();
+        match tokens.next_token() {
+            Ok(Some(token)) => last = Some((token, start)),
+            Ok(None) => break,
+            Err(_) => return input[start..].starts_with("/*").then_some("*/"),
+        }
+    }
+    match last {
+        Some((Token::Comment, start)) if input[start..].starts_with("//") => Some("\n"),
+        _ => None,
+    }
+}
+
+// Returns `partial` without any leading whitespace or comments.
+fn trim_insignificant_start(partial: &str) -> &str {
+    let mut tokens = Tokenizer::new(partial);
+    loop {
+        let start = tokens.pos();
+        match tokens.next_token() {
+            Ok(Some(token)) if token.is_insignificant() => (),
+            _ => return &partial[start..],
+        }
+    }
+}
+
 fn type_candidates(partial: &str, ty: &impl WasmType) -> Vec<String> {
-    let partial = partial.trim_start();
+    let partial = trim_insignificant_start(partial);
     use crate::WasmTypeKind::*;
     match ty.kind() {
         Char => string_like_completions(partial, "'"),
@@ -165,6 +214,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_comments() {
+        let ty = Type::list(Type::CHAR);
+        assert_candidates("/* c */ ", &ty, &["["]);
+        assert_candidates("[ // c\n", &ty, &["'"]);
+        assert_candidates("[/* c */ '", &ty, &[]);
+        assert_candidates("[/* c */ 'x", &ty, &["'"]);
+        assert_candidates("['x', /* c", &ty, &["*/"]);
+        assert_candidates("['x', // c", &ty, &["\n"]);
+        assert_candidates("['x' /* c */", &ty, &[",", "]"]);
+        assert_candidates("/* c */ '", &Type::CHAR, &[]);
+    }
+
     #[test]
     fn test_lists() {
         let ty = Type::list(Type::BOOL);
@@ -251,6 +313,18 @@ mod tests {
         assert_candidates("{write, ", &ty, &["read"]);
     }
 
+    #[test]
+    fn test_handles() {
+        let ty = Type::own("file");
+        assert_candidates("", &ty, &["file"]);
+        assert_candidates("fi", &ty, &["le"]);
+        assert_candidates("file", &ty, &["("]);
+        assert_candidates("file(", &ty, &["0"]);
+        assert_candidates("file(3", &ty, &[")"]);
+        assert_candidates("str", &Type::stream(Some(Type::U8)), &["eam"]);
+        assert_candidates("", &Type::ERROR_CONTEXT, &["error-context"]);
+    }
+
     #[test]
     fn test_params_completion() {
         let params = [Type::BOOL, Type::option(Type::BOOL)];
@@ -2,23 +2,47 @@
 
 use std::fmt::Display;
 
-use crate::{func::WasmFunc, ty::WasmTypeKind, writer::Writer, WasmType, WasmValue};
+use crate::{
+    func::WasmFunc,
+    lex::{self, Token, Tokenizer},
+    parser::ParserError,
+    ty::WasmTypeKind,
+    writer::{FmtWrite, Writer},
+    WasmType, WasmValue,
+};
 
 /// Implements a WAVE-formatted [`Display`] for a [`WasmType`].
+///
+/// With the alternate flag (`{:#}`), named types are written as their
+/// [`type_name`](WasmType::type_name) rather than their structure.
 pub struct DisplayType<T: WasmType>(pub T);
 
 impl<T: WasmType> Display for DisplayType<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let ty = &self.0;
+        if f.alternate() {
+            if let Some(name) = ty.type_name() {
+                return f.write_str(&name);
+            }
+        }
+        // Nested types are written with the same formatter to keep its flags
         match ty.kind() {
-            WasmTypeKind::List => write!(f, "list<{}>", Self(ty.list_element_type().unwrap())),
+            WasmTypeKind::List => {
+                f.write_str("list<")?;
+                Self(ty.list_element_type().unwrap()).fmt(f)?;
+                if let Some(length) = ty.list_length() {
+                    write!(f, ", {length}")?;
+                }
+                f.write_str(">")
+            }
             WasmTypeKind::Record => {
                 f.write_str("record { ")?;
                 for (idx, (name, field_type)) in ty.record_fields().enumerate() {
                     if idx != 0 {
                         f.write_str(", ")?;
                     }
-                    write!(f, "{name}: {}", Self(field_type))?;
+                    write!(f, "{name}: ")?;
+                    Self(field_type).fmt(f)?;
                 }
                 f.write_str(" }")
             }
@@ -28,7 +52,7 @@ impl<T: WasmType> Display for DisplayType<T> {
                     if idx != 0 {
                         f.write_str(", ")?;
                     }
-                    write!(f, "{}", Self(ty))?;
+                    Self(ty).fmt(f)?;
                 }
                 f.write_str(">")
             }
@@ -40,7 +64,9 @@ impl<T: WasmType> Display for DisplayType<T> {
                     }
                     f.write_str(name.as_ref())?;
                     if let Some(ty) = payload {
-                        write!(f, "({})", Self(ty))?;
+                        f.write_str("(")?;
+                        Self(ty).fmt(f)?;
+                        f.write_str(")")?;
                     }
                 }
                 f.write_str(" }")
@@ -56,16 +82,30 @@ impl<T: WasmType> Display for DisplayType<T> {
                 f.write_str(" }")
             }
             WasmTypeKind::Option => {
-                write!(f, "option<{}>", Self(ty.option_some_type().unwrap()))
+                f.write_str("option<")?;
+                Self(ty.option_some_type().unwrap()).fmt(f)?;
+                f.write_str(">")
             }
             WasmTypeKind::Result => {
                 f.write_str("result")?;
                 match ty.result_types().unwrap() {
-                    (None, None) => Ok(()),
-                    (None, Some(err)) => write!(f, "<_, {}>", Self(err)),
-                    (Some(ok), None) => write!(f, "<{}>", Self(ok)),
-                    (Some(ok), Some(err)) => write!(f, "<{}, {}>", Self(ok), Self(err)),
+                    (None, None) => return Ok(()),
+                    (None, Some(err)) => {
+                        f.write_str("<_, ")?;
+                        Self(err).fmt(f)?;
+                    }
+                    (Some(ok), None) => {
+                        f.write_str("<")?;
+                        Self(ok).fmt(f)?;
+                    }
+                    (Some(ok), Some(err)) => {
+                        f.write_str("<")?;
+                        Self(ok).fmt(f)?;
+                        f.write_str(", ")?;
+                        Self(err).fmt(f)?;
+                    }
                 }
+                f.write_str(">")
             }
             WasmTypeKind::Flags => {
                 f.write_str("flags { ")?;
@@ -77,6 +117,23 @@ impl<T: WasmType> Display for DisplayType<T> {
                 }
                 f.write_str(" }")
             }
+            kind @ (WasmTypeKind::Own | WasmTypeKind::Borrow) => {
+                write!(f, "{kind}<{}>", ty.resource_name().unwrap())
+            }
+            kind @ (WasmTypeKind::Stream | WasmTypeKind::Future) => {
+                let inner = if kind == WasmTypeKind::Stream {
+                    ty.stream_element_type().unwrap()
+                } else {
+                    ty.future_payload_type().unwrap()
+                };
+                Display::fmt(&kind, f)?;
+                if let Some(inner) = inner {
+                    f.write_str("<")?;
+                    Self(inner).fmt(f)?;
+                    f.write_str(">")?;
+                }
+                Ok(())
+            }
             simple => Display::fmt(&simple, f),
         }
     }
@@ -87,11 +144,186 @@ pub struct DisplayValue<'a, T: WasmValue>(pub &'a T);
 
 impl<'a, T: WasmValue> Display for DisplayValue<'a, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut buf = vec![];
-        Writer::new(&mut buf)
+        Writer::new(FmtWrite(f))
+            .write_value(self.0)
+            .map_err(|_| std::fmt::Error)
+    }
+}
+
+/// Implements a [`Display`] for a [`WasmValue`] that is WAVE-formatted
+/// except that parts of it are elided to stay within the given [`Limits`],
+/// e.g. for logging large values. The output may not be parseable.
+/// ```
+/// # use wasm_wave::{fmt::{DisplayTruncated, Limits}, value::Value};
+/// let limits = Limits { max_elements: 3, ..Default::default() };
+/// let val = Value::from([1u8; 1000]);
+/// assert_eq!(DisplayTruncated(&val, &limits).to_string(), "[1, 1, 1, …(997 more)]");
+/// ```
+pub struct DisplayTruncated<'a, T: WasmValue>(pub &'a T, pub &'a Limits);
+
+impl<'a, T: WasmValue> Display for DisplayTruncated<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Writer::new(FmtWrite(f))
+            .limits(self.1.clone())
+            .write_value(self.0)
+            .map_err(|_| std::fmt::Error)
+    }
+}
+
+/// Limits on the output of [`DisplayTruncated`]. Parts of values beyond
+/// these limits are elided and marked with `…`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Limits {
+    /// The max number of list elements written; the rest are counted, e.g.
+    /// `[1, 2, …(3 more)]`
+    pub max_elements: usize,
+    /// The max number of chars of each string written; the rest are
+    /// counted, e.g. `"abc"…(3 more)`
+    pub max_string_len: usize,
+    /// The max nesting depth of lists, records, tuples, and flags; deeper
+    /// ones are written as e.g. `[…]`
+    pub max_depth: usize,
+    /// The max number of bytes written in total, not counting a final `…`
+    pub max_bytes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_elements: 32,
+            max_string_len: 256,
+            max_depth: 8,
+            max_bytes: 4096,
+        }
+    }
+}
+
+/// Implements a WAVE-formatted [`Display`] for a [`WasmValue`] that is
+/// syntax-highlighted with the given ANSI terminal [`Styles`].
+/// ```
+/// # use wasm_wave::{fmt::{DisplayHighlighted, Styles}, value::Value};
+/// let styles = Styles { string: "32".into(), ..Styles::plain() };
+/// let val = Value::from(["a"]);
+/// assert_eq!(DisplayHighlighted(&val, &styles).to_string(), "[\x1b[32m\"a\"\x1b[0m]");
+/// ```
+pub struct DisplayHighlighted<'a, T: WasmValue>(pub &'a T, pub &'a Styles);
+
+impl<'a, T: WasmValue> Display for DisplayHighlighted<'a, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if *self.1 == Styles::plain() {
+            return DisplayValue(self.0).fmt(f);
+        }
+        let mut wave = String::new();
+        Writer::new(FmtWrite(&mut wave))
             .write_value(self.0)
             .map_err(|_| std::fmt::Error)?;
-        f.write_str(String::from_utf8_lossy(&buf).as_ref())
+        self.1.highlight(&wave, f)
+    }
+}
+
+/// ANSI terminal styles for each category of token, used for syntax
+/// highlighting by [`DisplayHighlighted`].
+///
+/// Each style is a sequence of ANSI SGR parameters, e.g. `"1;32"` for bold
+/// green. Tokens with an empty style are written unstyled.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Styles {
+    /// Style for strings and chars
+    pub string: String,
+    /// Style for numbers, including their signs
+    pub number: String,
+    /// Style for keywords: `true`, `false`, `inf`, `nan`, `some`, `none`,
+    /// `ok`, and `err`
+    pub keyword: String,
+    /// Style for record field, variant and enum case, and flag labels
+    pub label: String,
+    /// Style for brackets, commas, and colons
+    pub punctuation: String,
+    /// Style for comments
+    pub comment: String,
+}
+
+impl Styles {
+    /// Returns styles that leave every token unstyled.
+    pub fn plain() -> Self {
+        Self {
+            string: String::new(),
+            number: String::new(),
+            keyword: String::new(),
+            label: String::new(),
+            punctuation: String::new(),
+            comment: String::new(),
+        }
+    }
+
+    /// Returns the [`Default`] styles if the given stream is a terminal and
+    /// the `NO_COLOR` environment variable isn't set, or [`plain`](Self::plain)
+    /// styles otherwise.
+    pub fn for_stream(stream: &impl std::io::IsTerminal) -> Self {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|val| !val.is_empty());
+        if stream.is_terminal() && !no_color {
+            Self::default()
+        } else {
+            Self::plain()
+        }
+    }
+
+    /// Writes the given WAVE-encoded text to `w`, with each token styled by
+    /// its category. Anything after a lexing error is written unstyled.
+    pub fn highlight(&self, wave: &str, w: &mut impl std::fmt::Write) -> std::fmt::Result {
+        let mut pos = 0;
+        let mut tokens = Tokenizer::new(wave).peekable();
+        while let Some(Ok((token, mut span))) = tokens.next() {
+            // A sign and the number or `inf` right after it are one number
+            if let (Token::Dash, Some(Ok((next_token, next)))) = (token, tokens.peek()) {
+                let signed = match next_token {
+                    Token::Number => true,
+                    Token::Name => &wave[next.clone()] == "inf",
+                    _ => false,
+                };
+                if signed && next.start == span.end {
+                    span.end = next.end;
+                    tokens.next();
+                }
+            }
+            let text = &wave[span.clone()];
+            let style = match token {
+                Token::Whitespace => "",
+                Token::Comment => &self.comment,
+                Token::Dash | Token::Number => &self.number,
+                Token::Colon
+                | Token::Comma
+                | Token::LCurly
+                | Token::RCurly
+                | Token::LParen
+                | Token::RParen
+                | Token::LSquare
+                | Token::RSquare => &self.punctuation,
+                Token::Name if lex::KEYWORDS.contains(&text) => &self.keyword,
+                Token::Name => &self.label,
+                Token::Char | Token::String => &self.string,
+            };
+            if style.is_empty() {
+                w.write_str(text)?;
+            } else {
+                write!(w, "\x1b[{style}m{text}\x1b[0m")?;
+            }
+            pos = span.end;
+        }
+        w.write_str(&wave[pos..])
+    }
+}
+
+impl Default for Styles {
+    fn default() -> Self {
+        Self {
+            string: "32".into(),
+            number: "36".into(),
+            keyword: "35".into(),
+            label: "34".into(),
+            punctuation: String::new(),
+            comment: "2".into(),
+        }
     }
 }
 
@@ -122,9 +354,12 @@ impl<T: WasmFunc> Display for DisplayFunc<T> {
         if results.len() == 1 {
             let ty = DisplayType(results.into_iter().next().unwrap());
             if let Some(name) = result_names.next() {
-                write!(f, " -> ({name}: {ty})")
+                write!(f, " -> ({name}: ")?;
+                ty.fmt(f)?;
+                f.write_str(")")
             } else {
-                write!(f, " -> {ty}")
+                f.write_str(" -> ")?;
+                ty.fmt(f)
             }
         } else {
             f.write_str(" -> (")?;
@@ -171,9 +406,52 @@ impl<'a, T: WasmValue> Display for DisplayFuncResults<'a, T> {
     }
 }
 
+/// Implements a [`Display`] for a [`ParserError`] that shows the offending
+/// line of the given source input with the error span underlined.
+///
+/// The source must be the input the error was returned for.
+/// ```text
+/// error: error parsing int: number too large to fit in target type at 2:7
+///   |
+/// 2 |   {a: 1000},
+///   |       ^^^^
+/// ```
+pub struct DisplayParserError<'a>(pub &'a ParserError, pub &'a str);
+
+impl<'a> Display for DisplayParserError<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (err, src) = (self.0, self.1);
+        writeln!(f, "error: {err}")?;
+
+        let span = err.span();
+        let start = span.start.min(src.len());
+        let line_start = src[..start].rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        let line_end = src[start..]
+            .find('\n')
+            .map(|idx| start + idx)
+            .unwrap_or(src.len());
+        let line = src[line_start..line_end].trim_end_matches('\r');
+
+        // Align the underline with the span, preserving any tabs
+        let indent = src[line_start..start]
+            .chars()
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let underline_end = span.end.clamp(start, line_start + line.len());
+        let underline_len = src[start..underline_end].chars().count().max(1);
+
+        let line_num = err.location().line.to_string();
+        let gutter = " ".repeat(line_num.len());
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line_num} | {line}")?;
+        write!(f, "{gutter} | {indent}{}", "^".repeat(underline_len))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::value::Type;
+    use super::{DisplayHighlighted, DisplayParserError, DisplayTruncated, Limits, Styles};
+    use crate::value::{Type, Value};
 
     #[test]
     fn test_type_display() {
@@ -212,4 +490,140 @@ mod tests {
             assert_eq!(ty.to_string(), expected);
         }
     }
+
+    #[test]
+    fn test_parser_error_display() {
+        let ty = Type::record([("a", Type::U8)]).unwrap();
+        let input = "{\n\ta: 1,\n\tb: 2,\n}";
+        let err = crate::from_str::<Value>(&ty, input).unwrap_err();
+        assert_eq!(
+            DisplayParserError(&err, input).to_string(),
+            "error: expected [RCurly], got Some(Name) at 3:2\n  |\n3 | \tb: 2,\n  | \t^"
+        );
+
+        let input = "[1, 2, 1000]";
+        let err = crate::from_str::<Value>(&Type::list(Type::U8), input).unwrap_err();
+        assert_eq!(
+            DisplayParserError(&err, input).to_string(),
+            "error: error parsing int: number too large to fit in target type at 1:8\n  |\n1 | [1, 2, 1000]\n  |        ^^^^"
+        );
+    }
+
+    #[test]
+    fn test_highlighted_display() {
+        let ty = Type::record([
+            ("none", Type::option(Type::S8)),
+            ("b", Type::list(Type::FLOAT32)),
+            ("c", Type::CHAR),
+        ])
+        .unwrap();
+        let val: Value = crate::from_str(&ty, "{%none: some(-1), b: [-inf], c: 'x'}").unwrap();
+        let styles = Styles {
+            punctuation: "1".into(),
+            ..Styles::default()
+        };
+        let expected = [
+            ("1", "{"),
+            ("34", "%none"),
+            ("1", ":"),
+            ("", " "),
+            ("35", "some"),
+            ("1", "("),
+            ("36", "-1"),
+            ("1", ")"),
+            ("1", ","),
+            ("", " "),
+            ("34", "b"),
+            ("1", ":"),
+            ("", " "),
+            ("1", "["),
+            ("36", "-inf"),
+            ("1", "]"),
+            ("1", ","),
+            ("", " "),
+            ("34", "c"),
+            ("1", ":"),
+            ("", " "),
+            ("32", "'x'"),
+            ("1", "}"),
+        ]
+        .map(|(style, text)| match style {
+            "" => text.to_string(),
+            _ => format!("\x1b[{style}m{text}\x1b[0m"),
+        })
+        .concat();
+        assert_eq!(DisplayHighlighted(&val, &styles).to_string(), expected);
+
+        // Plain styles and non-terminals are unstyled
+        let plain = crate::to_string(&val).unwrap();
+        assert_eq!(
+            DisplayHighlighted(&val, &Styles::plain()).to_string(),
+            plain
+        );
+        let file = std::fs::File::open(file!()).unwrap();
+        assert_eq!(Styles::for_stream(&file), Styles::plain());
+
+        // Comments are styled too
+        let mut out = String::new();
+        Styles::default().highlight("1 // one", &mut out).unwrap();
+        assert_eq!(out, "\x1b[36m1\x1b[0m \x1b[2m// one\x1b[0m");
+    }
+
+    #[test]
+    fn test_truncated_display() {
+        let ty =
+            Type::record([("s", Type::STRING), ("l", Type::list(Type::list(Type::U8)))]).unwrap();
+        let val: Value = crate::from_str(&ty, r#"{s: "héllo", l: [[1, 2], [], [3]]}"#).unwrap();
+        for (limits, expected) in [
+            (Limits::default(), r#"{s: "héllo", l: [[1, 2], [], [3]]}"#),
+            (
+                Limits {
+                    max_elements: 1,
+                    ..Default::default()
+                },
+                r#"{s: "héllo", l: [[1, …(1 more)], …(2 more)]}"#,
+            ),
+            (
+                Limits {
+                    max_string_len: 2,
+                    ..Default::default()
+                },
+                r#"{s: "hé"…(3 more), l: [[1, 2], [], [3]]}"#,
+            ),
+            (
+                Limits {
+                    max_depth: 2,
+                    ..Default::default()
+                },
+                r#"{s: "héllo", l: [[…], [], […]]}"#,
+            ),
+            (
+                Limits {
+                    max_depth: 0,
+                    ..Default::default()
+                },
+                "{…}",
+            ),
+            (
+                Limits {
+                    max_bytes: 7,
+                    ..Default::default()
+                },
+                r#"{s: "h…"#,
+            ),
+            (
+                Limits {
+                    max_bytes: 8,
+                    ..Default::default()
+                },
+                r#"{s: "hé…"#,
+            ),
+        ] {
+            assert_eq!(
+                DisplayTruncated(&val, &limits).to_string(),
+                expected,
+                "for {limits:?}"
+            );
+        }
+    }
 }
@@ -2,7 +2,11 @@
 
 use std::borrow::Cow;
 
-use crate::{parser::ParserError, WasmType};
+use crate::{
+    lex::{Span, Token, Tokenizer},
+    parser::{Location, Parser, ParserError, ParserErrorKind},
+    WasmType, WasmValue,
+};
 
 /// Represents an unparsed Web Assembly func call.
 pub struct CallExpr<'a> {
@@ -15,21 +19,162 @@ pub struct CallExpr<'a> {
 impl<'a> CallExpr<'a> {
     /// Returns a func expr parsed from the given `expr`.
     pub fn parse(expr: &'a str) -> Result<Self, ParserError> {
-        let paren_idx = expr
-            .find('(')
-            .ok_or_else(|| ParserError::ParseParams("no opening paren in call expr".into()))?;
-        let (name, args) = expr.split_at(paren_idx);
-        let func_name = name.trim();
-        let args = args.trim();
-        if !args.ends_with(')') {
-            return Err(ParserError::ParseParams(
-                "no closing paren in call expr".into(),
-            ));
-        }
+        let (func_name, paren_idx) = split_func_name(expr)?;
+        let args = match significant_tokens(expr).last() {
+            Some((Some(Token::RParen), span)) => &expr[paren_idx..span.end],
+            _ => {
+                return Err(call_expr_error(
+                    expr,
+                    "no closing paren in call expr",
+                    paren_idx,
+                ))
+            }
+        };
         Ok(Self { func_name, args })
     }
 }
 
+/// Represents a Web Assembly func call parsed with the param and result
+/// types of a [`WasmFunc`], e.g. `my-func(1, "two") -> ok`.
+pub struct FuncCall<'a, V> {
+    /// The func name
+    pub func_name: &'a str,
+    /// The func args, in param order
+    pub args: Vec<V>,
+    /// The func results, if given after `->`
+    pub results: Option<Vec<V>>,
+}
+
+impl<'a, V: WasmValue + 'static> FuncCall<'a, V> {
+    /// Returns a func call parsed from the given `expr` with the param and
+    /// result types of the given [`WasmFunc`]. Args are parsed as by
+    /// [`Parser::parse_func_args`] and any results as by
+    /// [`Parser::parse_func_results`].
+    /// ```
+    /// use wasm_wave::{func::FuncCall, value::{FuncType, Type, Value}};
+    /// let func = FuncType::new(
+    ///     [("a".into(), Type::U8)],
+    ///     [("b".into(), Type::STRING), ("c".into(), Type::BOOL)],
+    /// )
+    /// .unwrap();
+    /// let call = FuncCall::<Value>::parse("f(7) -> (b: \"x\", c: true)", &func).unwrap();
+    /// assert_eq!(call.func_name, "f");
+    /// assert_eq!(call.args, [Value::from(7u8)]);
+    /// assert_eq!(call.results.unwrap(), [Value::from("x"), Value::from(true)]);
+    /// ```
+    pub fn parse<F>(expr: &'a str, func: &F) -> Result<Self, ParserError>
+    where
+        F: WasmFunc<Type = V::Type>,
+    {
+        let (func_name, paren_idx) = split_func_name(expr)?;
+        // Errors from parsers over a part of `expr` are moved to their offset
+        let offset_error =
+            |err: ParserError, offset| err.offset(offset, Location::from_offset(expr, offset));
+
+        let mut parser = Parser::new(&expr[paren_idx..]);
+        let args = parser
+            .parse_func_args(func)
+            .map_err(|err| offset_error(err, paren_idx))?;
+
+        // Look for `->` after the args
+        let rest_idx = paren_idx + parser.pos();
+        let rest = &expr[rest_idx..];
+        let mut tokens = Tokenizer::new(rest);
+        let results_idx = loop {
+            let start = tokens.pos();
+            match tokens.next_token() {
+                Ok(Some(token)) if token.is_insignificant() => (),
+                Ok(None) => {
+                    return Ok(Self {
+                        func_name,
+                        args,
+                        results: None,
+                    })
+                }
+                Ok(Some(Token::Dash)) if rest[tokens.pos()..].starts_with('>') => {
+                    break rest_idx + tokens.pos() + 1;
+                }
+                res => {
+                    let offset = rest_idx + start;
+                    return Err(ParserError::new(
+                        ParserErrorKind::UnexpectedTextAfterValue {
+                            got: res.ok().flatten(),
+                        },
+                        offset..offset + rest[start..].chars().next().map_or(0, char::len_utf8),
+                        Location::from_offset(expr, offset),
+                    ));
+                }
+            }
+        };
+
+        let mut parser = Parser::new(&expr[results_idx..]);
+        let results = parser
+            .parse_func_results(func)
+            .and_then(|results| {
+                parser.finish()?;
+                Ok(results)
+            })
+            .map_err(|err| offset_error(err, results_idx))?;
+        Ok(Self {
+            func_name,
+            args,
+            results: Some(results),
+        })
+    }
+}
+
+// Splits the func name from the rest of a call `expr`, returning the name
+// and the index of the opening paren of the args.
+fn split_func_name(expr: &str) -> Result<(&str, usize), ParserError> {
+    // Whitespace and comments are allowed around the name and args
+    let tokens = significant_tokens(expr);
+    let paren_pos = tokens
+        .iter()
+        .position(|(token, _)| *token == Some(Token::LParen))
+        .ok_or_else(|| call_expr_error(expr, "no opening paren in call expr", 0))?;
+    let func_name = match (tokens.first(), tokens[..paren_pos].last()) {
+        (Some((_, first)), Some((_, last))) => &expr[first.start..last.end],
+        _ => "",
+    };
+    Ok((func_name, tokens[paren_pos].1.start))
+}
+
+fn call_expr_error(expr: &str, msg: &str, offset: usize) -> ParserError {
+    ParserError::new(
+        ParserErrorKind::ParseParams(msg.into()),
+        offset..expr.len(),
+        Location::from_offset(expr, offset),
+    )
+}
+
+// Returns the tokens of `expr` other than whitespace and comments, with
+// their spans. Chars that don't start a valid token, including those of an
+// unterminated comment, are returned one at a time as `None` for the parser
+// to reject.
+fn significant_tokens(expr: &str) -> Vec<(Option<Token>, Span)> {
+    let mut tokens = Tokenizer::new(expr);
+    let mut significant = vec![];
+    loop {
+        let start = tokens.pos();
+        let mut next = tokens.clone();
+        let token = match next.next_token() {
+            Ok(None) => break,
+            Ok(Some(token)) => {
+                tokens = next;
+                Some(token)
+            }
+            Err(_) => {
+                tokens.skip_char();
+                None
+            }
+        };
+        if !token.is_some_and(Token::is_insignificant) {
+            significant.push((token, start..tokens.pos()));
+        }
+    }
+    significant
+}
+
 /// The WasmFunc trait may be implemented to represent Wasm func type
 /// signatures to be (de)serialized with WAVE.
 pub trait WasmFunc {
@@ -42,7 +187,7 @@ pub trait WasmFunc {
     /// Returns an iterator of the func's parameter names. Must be the same
     /// length as the iterator returned by `params` or empty if this WasmFunc
     /// impl does not support param names.
-    fn param_names(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn param_names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         Box::new(std::iter::empty())
     }
 
@@ -52,7 +197,164 @@ pub trait WasmFunc {
     /// Returns an iterator of the func's result names. Must be the same
     /// length as the iterator returned by `results` or empty if there are no
     /// named results or if this WasmFunc impl does not support result names.
-    fn result_names(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn result_names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         Box::new(std::iter::empty())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fmt::{DisplayFuncArgs, DisplayFuncResults},
+        value::{FuncType, Type, Value},
+    };
+
+    #[test]
+    fn call_expr_parse() {
+        for (expr, func_name, args) in [
+            ("f()", "f", "()"),
+            (" my-func ( 1, 2 ) ", "my-func", "( 1, 2 )"),
+            ("/* a */ f /* b */ (1) // c", "f", "(1)"),
+            ("f(\"/*\", '(') /* ) */", "f", "(\"/*\", '(')"),
+            ("f(1, // one\n 2)\n// end", "f", "(1, // one\n 2)"),
+        ] {
+            let call = CallExpr::parse(expr).unwrap_or_else(|err| panic!("{expr:?}: {err}"));
+            assert_eq!(call.func_name, func_name, "for {expr:?}");
+            assert_eq!(call.args, args, "for {expr:?}");
+        }
+        for expr in ["f", "f(1", "f(1) x", "/* ( */ f"] {
+            CallExpr::parse(expr).err().expect(expr);
+        }
+        // Unterminated comments are left in the args for the parser to reject
+        assert_eq!(CallExpr::parse("f(1) /* )").unwrap().args, "(1) /* )");
+    }
+
+    fn func_type(params: &[(&str, Type)], results: &[(&str, Type)]) -> FuncType {
+        let named = |types: &[(&str, Type)]| {
+            types
+                .iter()
+                .map(|(name, ty)| (name.to_string(), ty.clone()))
+                .collect::<Vec<_>>()
+        };
+        FuncType::new(named(params), named(results)).unwrap()
+    }
+
+    #[test]
+    fn func_call_parse() {
+        let pair = Type::tuple([Type::U8, Type::U8]).unwrap();
+        let no_results = func_type(&[("a", Type::U8)], &[]);
+        let one_result = func_type(&[("a", Type::U8)], &[("", pair.clone())]);
+        let named_result = func_type(&[], &[("out", Type::STRING)]);
+        let two_results = func_type(
+            &[("a", Type::option(Type::U8)), ("b", Type::U8)],
+            &[("x", Type::U8), ("y", Type::option(Type::BOOL))],
+        );
+        for (func, expr, want_args, want_results) in [
+            (&no_results, "f(1)", "(1)", None),
+            (&no_results, "f(1) -> ()", "(1)", Some("()")),
+            (
+                &no_results,
+                "f(a: 1) /* c */ -> ( ) // c",
+                "(1)",
+                Some("()"),
+            ),
+            (&one_result, "f(1) -> (2, 3)", "(1)", Some("(2, 3)")),
+            (&one_result, "f(1) -> (0: (2, 3))", "(1)", Some("(2, 3)")),
+            (&named_result, "f() -> \"x\"", "()", Some("\"x\"")),
+            (&named_result, "f() -> (out: \"x\",)", "()", Some("\"x\"")),
+            (&named_result, "f() -> (0: \"x\")", "()", Some("\"x\"")),
+            (
+                &two_results,
+                "f(b: 1) -> (x: 2, y: true)",
+                "(none, 1)",
+                Some("(2, some(true))"),
+            ),
+            (
+                &two_results,
+                "f(b: 1) -> (y: true, x: 2)",
+                "(none, 1)",
+                Some("(2, some(true))"),
+            ),
+            (
+                &two_results,
+                "f(b: 1) -> (1: none, x: 2)",
+                "(none, 1)",
+                Some("(2, none)"),
+            ),
+            (
+                &two_results,
+                "f(some(1), 2) -> (0: 3, 1: none)",
+                "(some(1), 2)",
+                Some("(3, none)"),
+            ),
+            (
+                &two_results,
+                "f(none, 2) -> (3, false)",
+                "(none, 2)",
+                Some("(3, some(false))"),
+            ),
+        ] {
+            let call = FuncCall::<Value>::parse(expr, func)
+                .unwrap_or_else(|err| panic!("{expr:?}: {err}"));
+            assert_eq!(call.func_name, "f", "for {expr:?}");
+            assert_eq!(
+                DisplayFuncArgs(&call.args).to_string(),
+                want_args,
+                "for {expr:?}"
+            );
+            let results = call
+                .results
+                .map(|results| DisplayFuncResults(&results).to_string());
+            assert_eq!(results.as_deref(), want_results, "for {expr:?}");
+        }
+
+        for (func, expr, span) in [
+            (&no_results, "f(1) x", 5..6),
+            (&no_results, "f(1) -> (1)", 9..10),
+            (&no_results, "f(300)", 2..5),
+            (&one_result, "f(1) -> (2, 3) 4", 15..16),
+            (&named_result, "f() -> (x: \"x\")", 8..9),
+            (&two_results, "f(b: 1) -> (x: 2, true)", 18..22),
+            (&two_results, "f(b: 1) -> (y: true, 0: 2, x: 3)", 27..28),
+            (&two_results, "f(b: 1) -> (y: true, z: 2)", 21..22),
+            (&two_results, "f(b: 1) -> (y: true)", 11..20),
+        ] {
+            let err = FuncCall::<Value>::parse(expr, func)
+                .err()
+                .unwrap_or_else(|| panic!("no error for {expr:?}"));
+            assert_eq!(err.span(), span, "for {expr:?}: {err}");
+        }
+    }
+
+    #[test]
+    fn func_call_round_trip() {
+        let func = func_type(
+            &[
+                ("a", Type::option(Type::STRING)),
+                ("b", Type::list(Type::CHAR)),
+            ],
+            &[
+                ("x", Type::result(Some(Type::U8), None)),
+                ("y", Type::option(Type::U8)),
+            ],
+        );
+        let args = [
+            crate::from_str::<Value>(&Type::option(Type::STRING), "some(\"a\")").unwrap(),
+            crate::from_str(&Type::list(Type::CHAR), "['b', '\\n']").unwrap(),
+        ];
+        let results = [
+            crate::from_str::<Value>(&Type::result(Some(Type::U8), None), "err").unwrap(),
+            crate::from_str(&Type::option(Type::U8), "none").unwrap(),
+        ];
+        let expr = format!(
+            "my-func{} -> {}",
+            DisplayFuncArgs(&args),
+            DisplayFuncResults(&results)
+        );
+        let call = FuncCall::<Value>::parse(&expr, &func).unwrap();
+        assert_eq!(call.func_name, "my-func");
+        assert_eq!(call.args, args);
+        assert_eq!(call.results.unwrap(), results);
+    }
+}
@@ -1,8 +1,32 @@
-use std::{ops::Range, slice::SliceIndex, str::Chars};
+use std::{cell::Cell, ops::Range, slice::SliceIndex, str::Chars};
+
+use crate::parser::Location;
+
+/// Names with special meaning in values, unless `%`-prefixed.
+pub(crate) const KEYWORDS: &[&str] = &["true", "false", "inf", "nan", "some", "none", "ok", "err"];
+
+/// Returns true if the given label is a valid kebab-case identifier: one or
+/// more dash-separated words, each starting with a letter and either all
+/// lowercase or all uppercase.
+pub(crate) fn is_label(label: &str) -> bool {
+    label.split('-').all(|word| {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) if first.is_ascii_lowercase() => {
+                chars.all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit())
+            }
+            Some(first) if first.is_ascii_uppercase() => {
+                chars.all(|ch| ch.is_ascii_uppercase() || ch.is_ascii_digit())
+            }
+            _ => false,
+        }
+    })
+}
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Token {
     Whitespace,
+    Comment,
 
     Dash,
     Colon,
@@ -21,6 +45,11 @@ pub enum Token {
 }
 
 impl Token {
+    /// Returns true if this token is insignificant (whitespace or a comment).
+    pub fn is_insignificant(self) -> bool {
+        matches!(self, Token::Whitespace | Token::Comment)
+    }
+
     /// Returns the single literal char that this token represents, if any.
     pub fn as_char(self) -> Option<char> {
         Some(match self {
@@ -40,17 +69,25 @@ impl Token {
 
 pub type Span = Range<usize>;
 
+#[derive(Clone)]
 pub struct Tokenizer<'a> {
     input: &'a str,
     pos: usize,
+    // The most recently computed (position, location), used to avoid
+    // rescanning the input from the start for every location lookup.
+    location_cache: Cell<(usize, Location)>,
 }
 
 impl<'a> Tokenizer<'a> {
     pub fn new(input: &'a str) -> Self {
-        Self { input, pos: 0 }
+        Self {
+            input,
+            pos: 0,
+            location_cache: Cell::new((0, Location::START)),
+        }
     }
 
-    pub fn get_span(&self, span: impl SliceIndex<str, Output = str>) -> &str {
+    pub fn get_span(&self, span: impl SliceIndex<str, Output = str>) -> &'a str {
         self.input.get(span).unwrap()
     }
 
@@ -58,6 +95,40 @@ impl<'a> Tokenizer<'a> {
         self.pos
     }
 
+    pub fn input_len(&self) -> usize {
+        self.input.len()
+    }
+
+    /// Returns the [`Location`] of the given byte position in the input.
+    pub fn location(&self, pos: usize) -> Location {
+        let pos = pos.min(self.input.len());
+        let (cached_pos, cached) = self.location_cache.get();
+        let location = if cached_pos <= pos {
+            cached.advance(self.get_span(cached_pos..pos))
+        } else {
+            Location::START.advance(self.get_span(..pos))
+        };
+        self.location_cache.set((pos, location));
+        location
+    }
+
+    /// Returns the span of input covered by the given [`LexError`], which
+    /// must have been returned by a `next_token` call starting at `start`.
+    pub fn error_span(&self, err: &LexError, start: usize) -> Span {
+        match err {
+            LexError::UnexpectedChar(pos) => {
+                let len = self.next_chars_at(*pos).next().map_or(0, char::len_utf8);
+                *pos..*pos + len
+            }
+            LexError::UnexpectedEnd => start..self.input.len(),
+        }
+    }
+
+    /// Skips the next char, e.g. after an [`LexError::UnexpectedChar`].
+    pub fn skip_char(&mut self) {
+        self.pos += self.next_chars().next().map_or(0, char::len_utf8);
+    }
+
     pub fn ended(&self) -> bool {
         self.pos == self.input.len()
     }
@@ -75,6 +146,11 @@ impl<'a> Tokenizer<'a> {
             return Ok(Some(Token::Whitespace));
         }
 
+        if ch == '/' {
+            self.eat_comment()?;
+            return Ok(Some(Token::Comment));
+        }
+
         // Single char tokens
         if let Some(token) = match ch {
             '-' => Some(Token::Dash),
@@ -106,8 +182,16 @@ impl<'a> Tokenizer<'a> {
                 Token::Name
             }
             '0'..='9' => {
-                // Eat characters from numbers (including decimals and exponents)
-                self.eat_while(|ch| matches!(ch, '0'..='9' | '-' | '.' | 'e' | 'E' | '+'));
+                let mut chars = self.next_chars();
+                if chars.next() == Some('0') && matches!(chars.next(), Some('x' | 'o' | 'b')) {
+                    // Eat a `0x`, `0o` or `0b` prefix and the following digits
+                    // and separators; the digits are validated by the parser
+                    self.pos += 2;
+                    self.eat_while(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+                } else {
+                    // Eat characters from numbers (including decimals and exponents)
+                    self.eat_while(|ch| matches!(ch, '0'..='9' | '-' | '.' | 'e' | 'E' | '+'));
+                }
                 Token::Number
             }
             '\'' => {
@@ -124,7 +208,11 @@ impl<'a> Tokenizer<'a> {
     }
 
     fn next_chars(&self) -> Chars<'a> {
-        self.input.get(self.pos..).unwrap().chars()
+        self.next_chars_at(self.pos)
+    }
+
+    fn next_chars_at(&self, pos: usize) -> Chars<'a> {
+        self.input.get(pos..).unwrap().chars()
     }
 
     fn eat_while(&mut self, f: impl Fn(char) -> bool) {
@@ -134,6 +222,29 @@ impl<'a> Tokenizer<'a> {
             .sum::<usize>();
     }
 
+    // Eat a `//` line comment (up to but not including the newline) or a
+    // `/* */` block comment. Block comments do not nest.
+    fn eat_comment(&mut self) -> Result<(), LexError> {
+        let rest = self.get_span(self.pos..);
+        if rest.starts_with("//") {
+            self.pos += rest.find('\n').unwrap_or(rest.len());
+            Ok(())
+        } else if let Some(block) = rest.strip_prefix("/*") {
+            match block.find("*/") {
+                Some(idx) => {
+                    self.pos += idx + 4;
+                    Ok(())
+                }
+                None => {
+                    self.pos += rest.len();
+                    Err(LexError::UnexpectedEnd)
+                }
+            }
+        } else {
+            Err(LexError::UnexpectedChar(self.pos))
+        }
+    }
+
     // Eat a string (one delimiter to the next, including any backslash-escaped delimiters)
     fn eat_string(&mut self, delim: char) -> Result<(), LexError> {
         let mut chars = self.next_chars();
@@ -3,9 +3,12 @@
 //! <https://github.com/lann/wave>
 #![deny(missing_docs)]
 
+pub mod ast;
 pub mod fmt;
 pub mod func;
+pub mod lines;
 pub mod parser;
+pub mod reader;
 pub mod value;
 pub mod writer;
 
@@ -23,6 +26,7 @@ pub use ty::{WasmType, WasmTypeKind};
 pub use val::WasmValue;
 
 use parser::Parser;
+use reader::Reader;
 use writer::Writer;
 
 /// Parses a [`WasmValue`] from the given WAVE-encoded string.
@@ -45,6 +49,31 @@ pub fn from_str<V: WasmValue>(ty: &V::Type, s: &str) -> Result<V, parser::Parser
     Ok(value)
 }
 
+/// Parses a [`WasmValue`] from the given WAVE-encoded [`std::io::Read`],
+/// reading it incrementally rather than all at once. See [`reader::Reader`].
+/// ```
+/// use wasmtime::component::{Type, Val};
+/// # fn main() -> Result<(), wasm_wave::parser::ParserError> {
+/// let input = std::io::Cursor::new(r#""👋 Hello, world! 👋""#);
+/// let val: Val = wasm_wave::from_reader(&Type::String, input)?;
+/// assert_eq!(val, Val::String("👋 Hello, world! 👋".into()));
+/// # Ok(())
+/// # }
+/// ```
+pub fn from_reader<V: WasmValue>(
+    ty: &V::Type,
+    r: impl std::io::Read,
+) -> Result<V, parser::ParserError> {
+    let mut reader = Reader::new(std::io::BufReader::new(r));
+
+    let value = reader.read_value(ty)?;
+
+    // Ensure that we've read the entire input.
+    reader.finish()?;
+
+    Ok(value)
+}
+
 /// WAVE-encodes a [`WasmValue`] into a string.
 /// ```
 /// use wasmtime::component::Val;
@@ -54,9 +83,9 @@ pub fn from_str<V: WasmValue>(ty: &V::Type, s: &str) -> Result<V, parser::Parser
 /// # Ok(())
 /// # }
 pub fn to_string(val: &impl WasmValue) -> Result<String, writer::WriterError> {
-    let mut buf = vec![];
-    Writer::new(&mut buf).write_value(val)?;
-    Ok(String::from_utf8(buf).unwrap_or_else(|err| panic!("invalid UTF-8: {err:?}")))
+    let mut s = String::new();
+    Writer::new(writer::FmtWrite(&mut s)).write_value(val)?;
+    Ok(s)
 }
 
 fn canonicalize_nan32(val: f32) -> f32 {
@@ -0,0 +1,242 @@
+//! WAVE Lines: a newline-delimited multi-value format.
+//!
+//! A WAVE Lines document holds one WAVE-encoded value per line, all of the
+//! same type. Lines that are blank or hold only whitespace and comments are
+//! skipped. Lines may end with `\n` or `\r\n`.
+//!
+//! ```
+//! use wasm_wave::{lines::{LinesReader, LinesWriter}, value::{Type, Value}};
+//! let mut buf = vec![];
+//! let mut writer = LinesWriter::new(&mut buf);
+//! writer.write_value(&Value::from("one\ntwo")).unwrap();
+//! writer.write_value(&Value::from("three")).unwrap();
+//! assert_eq!(buf, b"\"one\\ntwo\"\n\"three\"\n");
+//!
+//! let values = LinesReader::<_, Value>::new(&buf[..], Type::STRING)
+//!     .collect::<Result<Vec<_>, _>>()
+//!     .unwrap();
+//! assert_eq!(values, [Value::from("one\ntwo"), Value::from("three")]);
+//! ```
+
+use std::{
+    io::{self, BufRead, Write},
+    marker::PhantomData,
+};
+
+use crate::{
+    lex::Tokenizer,
+    parser::{Location, Parser, ParserError, ParserErrorKind},
+    val::WasmValue,
+    writer::{Writer, WriterError},
+};
+
+/// A WAVE Lines reader.
+///
+/// Iterates over the values of each non-blank line read from the wrapped
+/// `R` reader. An error on one line doesn't stop the following lines from
+/// being read, except for an error from the underlying reader. Error spans
+/// and locations are relative to the start of the whole input, so the
+/// [`location`](ParserError::location) line is the line number.
+pub struct LinesReader<R, V: WasmValue> {
+    inner: R,
+    ty: V::Type,
+    buf: Vec<u8>,
+    // The byte offset and 1-based number of the next line
+    offset: usize,
+    line: usize,
+    done: bool,
+    _value: PhantomData<fn() -> V>,
+}
+
+impl<R: BufRead, V: WasmValue> LinesReader<R, V> {
+    /// Returns a new LinesReader of values of the given type for the given
+    /// [`std::io::BufRead`].
+    pub fn new(r: R, ty: V::Type) -> Self {
+        Self {
+            inner: r,
+            ty,
+            buf: vec![],
+            offset: 0,
+            line: 1,
+            done: false,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<R: BufRead, V: WasmValue> Iterator for LinesReader<R, V> {
+    type Item = Result<V, ParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buf.clear();
+            let offset = self.offset;
+            let location = Location {
+                line: self.line,
+                column: 1,
+            };
+            let len = match self.inner.read_until(b'\n', &mut self.buf) {
+                Ok(0) => break,
+                Ok(len) => len,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    self.done = true;
+                    let err = ParserError::new(ParserErrorKind::Io(err), offset..offset, location);
+                    return Some(Err(err));
+                }
+            };
+            self.offset += len;
+            self.line += 1;
+
+            let line = match std::str::from_utf8(&self.buf) {
+                Ok(line) => line,
+                Err(_) => {
+                    let err = io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "stream did not contain valid UTF-8",
+                    );
+                    let span = offset..offset + len;
+                    return Some(Err(ParserError::new(
+                        ParserErrorKind::Io(err),
+                        span,
+                        location,
+                    )));
+                }
+            };
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            if is_blank(line) {
+                continue;
+            }
+
+            let mut parser = Parser::new(line);
+            let res = parser.parse_value(&self.ty).and_then(|val| {
+                parser.finish()?;
+                Ok(val)
+            });
+            return Some(res.map_err(|err| err.offset(offset, location)));
+        }
+        None
+    }
+}
+
+// Returns true if the given line holds only whitespace and comments.
+fn is_blank(line: &str) -> bool {
+    let mut tokens = Tokenizer::new(line);
+    loop {
+        match tokens.next_token() {
+            Ok(Some(token)) if token.is_insignificant() => (),
+            Ok(None) => return true,
+            _ => return false,
+        }
+    }
+}
+
+/// A WAVE Lines writer.
+///
+/// Writes each value to the wrapped `W` writer on its own line. Values are
+/// always written on a single line; newlines in chars and strings are
+/// escaped.
+pub struct LinesWriter<W> {
+    writer: Writer<W>,
+}
+
+impl<W: Write> LinesWriter<W> {
+    /// Returns a new LinesWriter for the given [`std::io::Write`].
+    pub fn new(w: W) -> Self {
+        Writer::new(w).into()
+    }
+
+    /// WAVE-encodes and writes the given [`WasmValue`] to the underlying
+    /// writer, followed by a newline.
+    pub fn write_value<V>(&mut self, val: &V) -> Result<(), WriterError>
+    where
+        V: WasmValue,
+    {
+        self.writer.write_value(val)?;
+        self.writer.as_mut().write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+impl<W: Write> From<Writer<W>> for LinesWriter<W> {
+    fn from(writer: Writer<W>) -> Self {
+        // Each value must be written on a single line
+        let writer = writer.pretty(false);
+        Self { writer }
+    }
+}
+
+impl<W> AsMut<W> for LinesWriter<W> {
+    fn as_mut(&mut self) -> &mut W {
+        self.writer.as_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::{Type, Value};
+
+    fn read_lines(input: &[u8], ty: &Type) -> Vec<Result<Value, ParserError>> {
+        LinesReader::new(input, ty.clone()).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        let ty = Type::record([("id", Type::U8), ("name", Type::STRING)]).unwrap();
+        let vals = ["{id: 1, name: \"a\\nb\"}", "{id: 2, name: \"\\r\\n\"}"]
+            .map(|input| crate::from_str::<Value>(&ty, input).unwrap());
+
+        let mut buf = vec![];
+        let mut writer = LinesWriter::new(&mut buf);
+        for val in &vals {
+            writer.write_value(val).unwrap();
+        }
+        assert_eq!(buf.iter().filter(|b| **b == b'\n').count(), 2);
+
+        let got = read_lines(&buf, &ty)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(got, vals);
+    }
+
+    #[test]
+    fn skips_blank_lines_and_comments() {
+        let input = b"// header\n\n1\r\n  \t\n2 // two\n/* three */ 3\n/* */\n4";
+        let got = read_lines(input, &Type::U8)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(got, [1u8, 2, 3, 4].map(Value::from));
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        let input = b"1\n\n300\n2\n  x\n3 /* open\n\"\xff\"\n4\n";
+        let got = read_lines(input, &Type::U8);
+        let summary = got
+            .iter()
+            .map(|res| match res {
+                Ok(val) => Ok(crate::to_string(val).unwrap()),
+                Err(err) => Err((err.location(), err.span())),
+            })
+            .collect::<Vec<_>>();
+        let loc = |line, column| Location { line, column };
+        assert_eq!(
+            summary,
+            [
+                Ok("1".to_string()),
+                Err((loc(3, 1), 3..6)),
+                Ok("2".to_string()),
+                Err((loc(5, 3), 11..12)),
+                Err((loc(6, 3), 15..22)),
+                Err((loc(7, 1), 23..27)),
+                Ok("4".to_string()),
+            ]
+        );
+        let Err(err) = &got[5] else { unreachable!() };
+        assert!(matches!(err.kind(), ParserErrorKind::Io(_)), "{err:?}");
+    }
+}
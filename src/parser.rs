@@ -1,16 +1,17 @@
 //! Web Assembly Value Encoding parser.
 
 use std::{
-    borrow::{Borrow, Cow},
+    borrow::{Borrow, BorrowMut, Cow},
     fmt::Display,
     num::{ParseFloatError, ParseIntError},
-    str::FromStr,
 };
 
 use indexmap::{IndexMap, IndexSet};
 
 use crate::{
+    ast::{Label, Node, NodeKind},
     completion::Completions,
+    func::WasmFunc,
     lex::{LexError, Span},
     lex::{Token, Tokenizer},
     ty::WasmTypeKind,
@@ -23,6 +24,7 @@ pub struct Parser<'a> {
     peeked: Option<Result<(Token, Span), ParserError>>,
     peeked_pos: usize,
     completion: bool,
+    recovery: Option<Recovery>,
 }
 
 pub(crate) const SOME: &str = "some";
@@ -40,11 +42,12 @@ impl<'a> Parser<'a> {
             peeked: None,
             peeked_pos: 0,
             completion: false,
+            recovery: None,
         }
     }
 
     /// Enable or disables the completions API, disabled by default. The
-    /// completion API is available from [`ParserError::UnexpectedEnd`]
+    /// completion API is available from [`ParserErrorKind::UnexpectedEnd`]
     /// errors returned from parsing.
     pub fn completion(&mut self, enabled: bool) {
         self.completion = enabled;
@@ -81,8 +84,17 @@ impl<'a> Parser<'a> {
             WasmTypeKind::Option => self.parse_option(ty)?,
             WasmTypeKind::Result => self.parse_result(ty)?,
             WasmTypeKind::Flags => self.parse_flags(ty)?,
+            WasmTypeKind::Own
+            | WasmTypeKind::Borrow
+            | WasmTypeKind::Stream
+            | WasmTypeKind::Future
+            | WasmTypeKind::ErrorContext => self.parse_handle(ty)?,
             WasmTypeKind::Unsupported => {
-                return Err(ParserError::Unsupported("unsupported type".into()))
+                let pos = self.pos();
+                return Err(self.error(
+                    ParserErrorKind::Unsupported("unsupported type".into()),
+                    pos..pos,
+                ));
             }
         })
     }
@@ -95,15 +107,230 @@ impl<'a> Parser<'a> {
         &mut self,
         types: impl IntoIterator<Item = &'ty V::Type>,
     ) -> Result<Vec<V>, ParserError> {
-        self.parse_params_inner(types)
+        self.parse_params_inner(types, false)
             .map_err(|err| self.handle_unexpected_end_errors(err, 0, None::<&V::Type>))
     }
 
+    /// Parses WAVE-encoded, parenthesized args for the given [`WasmFunc`].
+    ///
+    /// Args may be positional, as for [`Parser::parse_params`], or named,
+    /// e.g. `(b: 2, a: 1)`. Named args may be given in any order and any
+    /// option-typed param may be omitted, in which case it is returned as a
+    /// `none` value. Named and positional args can't be mixed. Values are
+    /// returned in param order.
+    /// ```
+    /// use wasm_wave::{parser::Parser, value::{FuncType, Type, Value}};
+    /// let func = FuncType::new(
+    ///     [("a".into(), Type::option(Type::U8)), ("b".into(), Type::U8)],
+    ///     [],
+    /// )
+    /// .unwrap();
+    /// let args: Vec<Value> = Parser::new("(b: 2)").parse_func_args(&func).unwrap();
+    /// assert_eq!(wasm_wave::to_string(&args[0]).unwrap(), "none");
+    /// assert_eq!(wasm_wave::to_string(&args[1]).unwrap(), "2");
+    /// ```
+    pub fn parse_func_args<V, F>(&mut self, func: &F) -> Result<Vec<V>, ParserError>
+    where
+        V: WasmValue + 'static,
+        F: WasmFunc<Type = V::Type>,
+    {
+        self.parse_func_args_inner(func)
+            .map_err(|err| self.handle_unexpected_end_errors(err, 0, None::<&V::Type>))
+    }
+
+    fn parse_func_args_inner<V, F>(&mut self, func: &F) -> Result<Vec<V>, ParserError>
+    where
+        V: WasmValue + 'static,
+        F: WasmFunc<Type = V::Type>,
+    {
+        let types = func.params().collect::<Vec<_>>();
+        if !self.peek_entry_after(Token::LParen, &[Token::Name]) {
+            return self.parse_params_inner(&types, true);
+        }
+        let names = func.param_names().collect::<Vec<_>>();
+        if names.len() != types.len() {
+            let span = self.peek_span();
+            return Err(self.error(
+                ParserErrorKind::ParseParams("func has no param names for named args".into()),
+                span,
+            ));
+        }
+
+        let start = self.expect(Token::LParen)?.start;
+        let values = self.parse_labeled_values(
+            &types,
+            &[Token::Name],
+            "arg",
+            mixed_args_error,
+            |parser, _, name, span| {
+                names.iter().position(|n| n == name).ok_or_else(|| {
+                    parser.unexpected_name(names.iter().map(|n| n.as_ref()), name, span)
+                })
+            },
+        )?;
+
+        names
+            .iter()
+            .zip(&types)
+            .zip(values)
+            .map(|((name, ty), val)| match val {
+                Some(val) => Ok(val),
+                // Omitted `option` arg; use "none"
+                None if ty.kind() == WasmTypeKind::Option => {
+                    V::make_option(ty, None).map_err(|err| self.make_value_error(err, start))
+                }
+                None => Err(self.error(
+                    ParserErrorKind::ParseParams(format!("missing arg `{name}`")),
+                    start..self.pos(),
+                )),
+            })
+            .collect()
+    }
+
+    /// Parses WAVE-encoded results for the given [`WasmFunc`], as follow
+    /// `->` in a func call.
+    ///
+    /// Results may be given as a parenthesized sequence of entries, each a
+    /// result label (for named results) or zero-based index followed by a
+    /// colon and the value, e.g. `(b: 2, a: 1)` or `(0: 1)`. As for named
+    /// args, labeled results may be given in any order, but unlike args,
+    /// none may be omitted. Results may also be given without labels, as
+    /// written by [`DisplayFuncResults`](crate::fmt::DisplayFuncResults):
+    /// zero results as `()`, a single result as the "flat" value itself, or
+    /// multiple results as a parenthesized sequence of values in result
+    /// order. Values are returned in result order.
+    pub fn parse_func_results<V, F>(&mut self, func: &F) -> Result<Vec<V>, ParserError>
+    where
+        V: WasmValue + 'static,
+        F: WasmFunc<Type = V::Type>,
+    {
+        let start = self.pos();
+        self.parse_func_results_inner(func)
+            .map_err(|err| self.handle_unexpected_end_errors(err, start, None::<&V::Type>))
+    }
+
+    fn parse_func_results_inner<V, F>(&mut self, func: &F) -> Result<Vec<V>, ParserError>
+    where
+        V: WasmValue + 'static,
+        F: WasmFunc<Type = V::Type>,
+    {
+        const KEYS: &[Token] = &[Token::Name, Token::Number];
+        let types = func.results().collect::<Vec<_>>();
+        let names = func.result_names().collect::<Vec<_>>();
+        let labeled = self.peek_entry_after(Token::LParen, KEYS);
+        if !labeled && types.len() == 1 {
+            return Ok(vec![self.parse_value(&types[0])?]);
+        }
+
+        let start = self.expect(Token::LParen)?.start;
+        if labeled {
+            let values = self.parse_labeled_values(
+                &types,
+                KEYS,
+                "result",
+                mixed_results_error,
+                |parser, token, key, span| {
+                    let idx = match token {
+                        Token::Name => names.iter().position(|name| name == key),
+                        _ => (0..types.len()).position(|idx| idx.to_string() == key),
+                    };
+                    idx.ok_or_else(|| {
+                        let names = names.iter().map(|name| name.to_string());
+                        let indexes = (0..types.len()).map(|idx| idx.to_string());
+                        parser.unexpected_name(names.chain(indexes), key, span)
+                    })
+                },
+            )?;
+            return values
+                .into_iter()
+                .enumerate()
+                .map(|(idx, val)| {
+                    val.ok_or_else(|| {
+                        let label = names.get(idx).map_or(idx.to_string(), |n| n.to_string());
+                        self.error(
+                            ParserErrorKind::ParseParams(format!("missing result `{label}`")),
+                            start..self.pos(),
+                        )
+                    })
+                })
+                .collect();
+        }
+
+        let mut values = Vec::with_capacity(types.len());
+        for ty in &types {
+            if self.peek_entry(KEYS) {
+                let span = self.peek_span();
+                return Err(self.error(mixed_results_error(), span));
+            }
+
+            values.push(self.parse_value(ty)?);
+
+            if values.len() == types.len() {
+                if let (Token::Comma, _) = self.expect_any_of(&[Token::RParen, Token::Comma])? {
+                    self.expect(Token::RParen)?;
+                }
+            } else {
+                self.expect(Token::Comma)?;
+            }
+        }
+        if types.is_empty() {
+            self.expect(Token::RParen)?;
+        }
+        Ok(values)
+    }
+
+    // Parses a parenthesized sequence of labeled entries, like `(b: 2, a:
+    // 1)`, after the opening paren. Entries may be in any order. Each label
+    // token, one of `keys`, is looked up in `types` with `position`, given
+    // the label without any `%` prefix. `entry` names an entry in errors and
+    // `mixed` is the error for an unlabeled entry. Returns the values in
+    // `types` order, with `None` for any not given.
+    fn parse_labeled_values<V: WasmValue>(
+        &mut self,
+        types: &[V::Type],
+        keys: &[Token],
+        entry: &str,
+        mixed: fn() -> ParserErrorKind,
+        position: impl Fn(&Self, Token, &str, Span) -> Result<usize, ParserError>,
+    ) -> Result<Vec<Option<V>>, ParserError> {
+        let mut values = vec![None; types.len()];
+        loop {
+            if self.maybe_close_delim(Token::RParen, false)? {
+                break;
+            }
+            if !self.peek_entry(keys) {
+                let span = self.peek_span();
+                return Err(self.error(mixed(), span));
+            }
+
+            let (token, span) = self.expect_any_of(keys)?;
+            let label = self.tokens.get_span(span.clone());
+            let label = label.strip_prefix('%').unwrap_or(label);
+            let idx = position(self, token, label, span.clone())?;
+            if values[idx].is_some() {
+                return Err(self.error(
+                    ParserErrorKind::ParseParams(format!("duplicate {entry} `{label}`")),
+                    span,
+                ));
+            }
+
+            self.expect(Token::Colon)?;
+
+            values[idx] = Some(self.parse_value(&types[idx])?);
+
+            if let (Token::RParen, _) = self.expect_any_of(&[Token::Comma, Token::RParen])? {
+                break;
+            }
+        }
+        Ok(values)
+    }
+
     fn parse_params_inner<'ty, V: WasmValue + 'static>(
         &mut self,
         types: impl IntoIterator<Item = &'ty V::Type>,
+        reject_named: bool,
     ) -> Result<Vec<V>, ParserError> {
-        self.expect(Token::LParen)?;
+        let start = self.expect(Token::LParen)?.start;
 
         let types = types.into_iter().collect::<Vec<_>>();
         let min_len = types
@@ -122,9 +349,19 @@ impl<'a> Parser<'a> {
                 break;
             }
 
-            let ty = types.next().ok_or_else(|| {
-                ParserError::ParseParams(format!("too many params; expected at most {max_len}"))
-            })?;
+            let Some(ty) = types.next() else {
+                let pos = self.pos();
+                return Err(self.error(
+                    ParserErrorKind::ParseParams(format!(
+                        "too many params; expected at most {max_len}"
+                    )),
+                    pos..pos,
+                ));
+            };
+            if reject_named && self.peek_entry(&[Token::Name]) {
+                let span = self.peek_span();
+                return Err(self.error(mixed_args_error(), span));
+            }
             values.push(self.parse_value(ty)?);
 
             if values.len() >= min_len {
@@ -137,12 +374,284 @@ impl<'a> Parser<'a> {
         }
         // Handle omitted trailing 'none' values
         for ty in types {
-            let none = V::make_option(ty, None).map_err(ParserError::make_value)?;
+            let none = V::make_option(ty, None).map_err(|err| self.make_value_error(err, start))?;
             values.push(none);
         }
         Ok(values)
     }
 
+    /// Parses a WAVE-encoded value of the given [`WasmType`], recovering from
+    /// errors to report as many of them as possible.
+    ///
+    /// After a syntax error the parser skips ahead to the next `,` or closing
+    /// delimiter and continues; delimiters left open at the end of the input
+    /// are closed. The returned value is a best-effort partial value: list
+    /// elements, record fields and flags with errors are left out (an
+    /// `option` field becomes `none`), and any other value with an error is
+    /// left out of its nearest enclosing list. Text after the value is
+    /// reported as an error but doesn't discard the value.
+    /// ```
+    /// use wasm_wave::{parser::Parser, value::{Type, Value}};
+    /// let ty = Type::list(Type::U8);
+    /// let recovered = Parser::new("[1, 256, 3, x]").parse_value_recovering::<Value>(&ty);
+    /// assert_eq!(recovered.value, Some(Value::from([1u8, 3])));
+    /// assert_eq!(recovered.errors.len(), 2);
+    /// ```
+    pub fn parse_value_recovering<V: WasmValue>(&mut self, ty: &V::Type) -> Recovered<V> {
+        self.recovery = Some(Recovery::default());
+        let node = self.parse_node();
+        let finished = node.is_ok().then(|| self.finish());
+        let mut recovery = self.recovery.take().unwrap();
+        if let Some(Err(err)) = finished {
+            recovery.record(err);
+        }
+        let value = match node {
+            Ok(node) => node.lower_recovering(ty, &mut recovery),
+            Err(err) => {
+                recovery.record(err);
+                None
+            }
+        };
+        let mut errors = recovery.errors;
+        errors.sort_by_key(|err| err.span().start);
+        Recovered { value, errors }
+    }
+
+    /// Parses a WAVE-encoded value into an untyped [`Node`] syntax tree,
+    /// without needing to know the type of the value. The node can later be
+    /// converted into a [`WasmValue`] with [`Node::to_wasm_value`].
+    pub fn parse_node(&mut self) -> Result<Node, ParserError> {
+        let start = self.peek_start();
+        let location = self.tokens.location(start);
+        let kind = match self.peek_token() {
+            Some(Token::Number | Token::Dash) => {
+                let span = self.expect_number(true)?;
+                let number = self.tokens.get_span(span.clone());
+                // Reject malformed numbers here rather than at conversion.
+                let valid = if split_radix(number).is_some() {
+                    i128::parse_number(number).map(|_| ())
+                } else {
+                    f64::parse_number(number).map(|_| ())
+                };
+                valid.map_err(|kind| self.error(kind, span))?;
+                NodeKind::Number(number.into())
+            }
+            Some(Token::Char) => NodeKind::Char(self.parse_char()?),
+            Some(Token::String) => NodeKind::String(self.parse_string()?.into()),
+            Some(Token::Name) => {
+                let label = self.parse_label()?;
+                let payload = if self.peek_token() == Some(Token::LParen) {
+                    self.expect(Token::LParen)?;
+                    let payload = self.parse_node_recovering()?;
+                    self.expect_recovering(&[Token::RParen])?;
+                    Some(Box::new(payload))
+                } else {
+                    None
+                };
+                NodeKind::VariantCase { label, payload }
+            }
+            Some(Token::LParen) => NodeKind::Tuple(self.parse_node_seq(Token::LParen)?),
+            Some(Token::LSquare) => NodeKind::List(self.parse_node_seq(Token::LSquare)?),
+            Some(Token::LCurly) => self.parse_record_or_flags_node()?,
+            _ => {
+                self.expect_any_of(&[
+                    Token::Number,
+                    Token::Dash,
+                    Token::Char,
+                    Token::String,
+                    Token::Name,
+                    Token::LParen,
+                    Token::LSquare,
+                    Token::LCurly,
+                ])?;
+                unreachable!("unexpected token should have been rejected")
+            }
+        };
+        Ok(Node::new(kind, start..self.pos(), location))
+    }
+
+    // Parses a tuple or list of nodes, starting with the given open delimiter.
+    fn parse_node_seq(&mut self, open: Token) -> Result<Vec<Node>, ParserError> {
+        self.expect(open)?;
+        let (close, allow_empty) = match open {
+            Token::LParen => (Token::RParen, false),
+            Token::LSquare => (Token::RSquare, true),
+            _ => unreachable!(),
+        };
+        let mut nodes = vec![];
+        loop {
+            if (allow_empty || !nodes.is_empty()) && self.maybe_close_delim(close, false)? {
+                break;
+            }
+
+            nodes.push(self.parse_node_recovering()?);
+
+            if self.expect_recovering(&[Token::Comma, close])? == close {
+                break;
+            }
+        }
+        Ok(nodes)
+    }
+
+    fn parse_record_or_flags_node(&mut self) -> Result<NodeKind, ParserError> {
+        self.expect(Token::LCurly)?;
+        // An empty `{}` is always flags; records must have at least one field.
+        if self.maybe_close_delim(Token::RCurly, false)? {
+            return Ok(NodeKind::Flags(vec![]));
+        }
+
+        // Decided by the first label: flags are followed by `,` or `}`
+        let mut is_record = None;
+        let mut fields = vec![];
+        let mut flags = vec![];
+        loop {
+            let label = self.parse_label();
+            if let Some(label) = self.recover(label)? {
+                if *is_record.get_or_insert_with(|| {
+                    !matches!(self.peek_token(), Some(Token::Comma | Token::RCurly))
+                }) {
+                    let value = self
+                        .expect(Token::Colon)
+                        .and_then(|_| self.parse_node_recovering());
+                    fields.push((label, self.recover_node(value)?));
+                } else {
+                    flags.push(label);
+                }
+            }
+
+            if self.expect_recovering(&[Token::Comma, Token::RCurly])? == Token::RCurly {
+                break;
+            }
+            if self.maybe_close_delim(Token::RCurly, false)? {
+                break;
+            }
+        }
+        Ok(if is_record == Some(true) {
+            NodeKind::Record(fields)
+        } else {
+            NodeKind::Flags(flags)
+        })
+    }
+
+    // Like `parse_node`, but in recovery mode an error is recorded and an
+    // error node is returned in place of the value.
+    fn parse_node_recovering(&mut self) -> Result<Node, ParserError> {
+        let node = self.parse_node();
+        self.recover_node(node)
+    }
+
+    fn recover_node(&mut self, node: Result<Node, ParserError>) -> Result<Node, ParserError> {
+        let start = match &node {
+            Err(err) => err.span().start,
+            Ok(_) => 0,
+        };
+        Ok(match self.recover(node)? {
+            Some(node) => node,
+            None => {
+                let location = self.tokens.location(start);
+                Node::new(NodeKind::Error, start..self.pos(), location)
+            }
+        })
+    }
+
+    // In recovery mode, records the given error and skips ahead to the next
+    // `,` or closing delimiter, returning None. Otherwise returns the error.
+    fn recover<T>(&mut self, res: Result<T, ParserError>) -> Result<Option<T>, ParserError> {
+        match res {
+            Ok(val) => Ok(Some(val)),
+            Err(err) if self.recovery.is_none() => Err(err),
+            Err(err) => {
+                self.unconsume_closer(&err);
+                self.recovery.as_mut().unwrap().record(err);
+                self.skip_to_delimiter();
+                Ok(None)
+            }
+        }
+    }
+
+    // Expects one of the given tokens, the last of which closes the value
+    // being parsed. In recovery mode, on error, skips ahead to the next `,`
+    // or closing delimiter and tries once more; at the end of the input the
+    // value is closed, as if by the last token.
+    fn expect_recovering(&mut self, expected: &[Token]) -> Result<Token, ParserError> {
+        let err = match self.expect_any_of(expected) {
+            Ok((token, _)) => return Ok(token),
+            Err(err) if self.recovery.is_none() => return Err(err),
+            Err(err) if is_end_of_input(&err) => {
+                self.recovery.as_mut().unwrap().record(err);
+                return Ok(*expected.last().unwrap());
+            }
+            Err(err) => err,
+        };
+        // A mismatched closing delimiter is left for an enclosing value
+        if !self.unconsume_closer(&err) {
+            self.recover::<()>(Err(err))?;
+            match self.expect_any_of(expected) {
+                Ok((token, _)) => return Ok(token),
+                Err(err) if is_end_of_input(&err) => {
+                    self.recovery.as_mut().unwrap().record(err);
+                    return Ok(*expected.last().unwrap());
+                }
+                Err(err) => {
+                    self.unconsume_closer(&err);
+                    return Err(err);
+                }
+            }
+        }
+        Err(err)
+    }
+
+    // If the given error is for an unexpected closing delimiter that was
+    // just consumed, puts it back so that an enclosing value can use it.
+    fn unconsume_closer(&mut self, err: &ParserError) -> bool {
+        let ParserErrorKind::UnexpectedToken {
+            got: Some(token @ (Token::RParen | Token::RSquare | Token::RCurly)),
+            ..
+        } = err.kind
+        else {
+            return false;
+        };
+        if self.peeked.is_some() || err.span.end != self.tokens.pos() {
+            return false;
+        }
+        self.peeked_pos = err.span.start;
+        self.peeked = Some(Ok((token, err.span.clone())));
+        true
+    }
+
+    // Skips tokens up to (but not including) the next `,` or closing
+    // delimiter that isn't nested in another delimited value.
+    fn skip_to_delimiter(&mut self) {
+        let mut depth = 0usize;
+        loop {
+            let (token, span) = match self.next_non_whitespace() {
+                Ok(Some(next)) => next,
+                Ok(None) => return,
+                Err(_) => {
+                    // Skip past an unexpected char; other lex errors end the input.
+                    self.tokens.skip_char();
+                    continue;
+                }
+            };
+            match token {
+                Token::LParen | Token::LSquare | Token::LCurly => depth += 1,
+                Token::Comma | Token::RParen | Token::RSquare | Token::RCurly if depth == 0 => {
+                    self.peeked_pos = span.start;
+                    self.peeked = Some(Ok((token, span)));
+                    return;
+                }
+                Token::RParen | Token::RSquare | Token::RCurly => depth -= 1,
+                _ => (),
+            }
+        }
+    }
+
+    fn parse_label(&mut self) -> Result<Label, ParserError> {
+        let (name, span) = self.parse_name()?;
+        let location = self.tokens.location(span.start);
+        Ok(Label::new(name, span, location))
+    }
+
     /// Returns the current byte position in the input.
     pub fn pos(&self) -> usize {
         if self.peeked.is_some() {
@@ -154,26 +663,38 @@ impl<'a> Parser<'a> {
 
     /// Return an error if we didn't reach the end of the input.
     pub fn finish(&mut self) -> Result<(), ParserError> {
-        if let Some((token, _span)) = self.peek_next_non_whitespace() {
-            return Err(ParserError::UnexpectedTextAfterValue { got: Some(token) });
+        if let Some((token, span)) = self.peek_next_non_whitespace() {
+            return Err(self.error(
+                ParserErrorKind::UnexpectedTextAfterValue { got: Some(token) },
+                span,
+            ));
+        }
+        // Report any lex error (e.g. an unterminated comment) after the value
+        if let Some(Err(err)) = self.peeked.take() {
+            return Err(err);
         }
         Ok(())
     }
 
     fn parse_bool(&mut self) -> Result<bool, ParserError> {
         let names = [TRUE, FALSE];
-        match self.expect_name(names)? {
+        let (name, span) = self.expect_name(names)?;
+        match name {
             TRUE => Ok(true),
             FALSE => Ok(false),
-            other => Err(ParserError::unexpected_name(names, other)),
+            other => Err(self.unexpected_name(names, other, span)),
         }
     }
 
-    fn parse_number<T>(&mut self, signed: bool) -> Result<T, ParserError>
-    where
-        T: FromStr,
-        ParserError: From<T::Err>,
-    {
+    fn parse_number<T: ParseNumber>(&mut self, signed: bool) -> Result<T, ParserError> {
+        let span = self.expect_number(signed)?;
+        let s = self.tokens.get_span(span.clone());
+        T::parse_number(s).map_err(|kind| self.error(kind, span))
+    }
+
+    // Returns the span of a number token, including any leading dash, or of
+    // one of the names `inf`, `-inf`, or `nan`.
+    fn expect_number(&mut self, signed: bool) -> Result<Span, ParserError> {
         let (mut token, mut span) = self.expect_any_of(if signed {
             &[Token::Number, Token::Dash, Token::Name]
         } else {
@@ -181,32 +702,41 @@ impl<'a> Parser<'a> {
         })?;
         if token == Token::Dash {
             // Whitespace isn't allowed here, so get the next token directly
-            match self.tokens.next_token()? {
-                Some(next_token @ Token::Number | next_token @ Token::Name) => {
+            match self.next_token()? {
+                Some((next_token @ Token::Number | next_token @ Token::Name, next_span)) => {
                     // Include leading dash in span
-                    span.end = self.tokens.pos();
+                    span.end = next_span.end;
                     token = next_token;
                 }
                 other => {
-                    return Err(ParserError::UnexpectedToken {
-                        expected: vec![Token::Number, Token::Name],
-                        got: other,
-                    })
+                    let (got, span) = match other {
+                        Some((token, span)) => (Some(token), span),
+                        None => (None, self.end_span()),
+                    };
+                    return Err(self.error(
+                        ParserErrorKind::UnexpectedToken {
+                            expected: vec![Token::Number, Token::Name],
+                            got,
+                        },
+                        span,
+                    ));
                 }
             }
         }
 
-        let s = self.tokens.get_span(span);
+        let s = self.tokens.get_span(span.clone());
 
         // Limit names to specific recognized names.
         if token == Token::Name && s != "inf" && s != "-inf" && s != "nan" {
-            return Err(ParserError::UnexpectedName {
-                expected: vec!["inf".to_string(), "-inf".to_string(), "nan".to_string()],
-                got: s.to_string(),
-            });
+            return Err(self.error(
+                ParserErrorKind::UnexpectedName {
+                    expected: vec!["inf".to_string(), "-inf".to_string(), "nan".to_string()],
+                    got: s.to_string(),
+                },
+                span,
+            ));
         }
-
-        Ok(s.parse()?)
+        Ok(span)
     }
 
     fn parse_char(&mut self) -> Result<char, ParserError> {
@@ -217,16 +747,19 @@ impl<'a> Parser<'a> {
         };
         let len = inner_span.len();
         if len == 0 {
-            return Err(ParserError::InvalidChar("empty"));
+            return Err(self.error(ParserErrorKind::InvalidChar("empty"), span));
         }
         let (ch, parsed, _) = self.parse_char_inner(inner_span)?;
         if parsed < len {
-            return Err(ParserError::InvalidChar("more than one character"));
+            return Err(self.error(
+                ParserErrorKind::InvalidChar("more than one character"),
+                span,
+            ));
         }
         Ok(ch)
     }
 
-    fn parse_string(&mut self) -> Result<Cow<str>, ParserError> {
+    fn parse_string(&mut self) -> Result<Cow<'a, str>, ParserError> {
         let span = self.expect(Token::String)?;
         let start = span.start + 1;
         let end = span.end - 1;
@@ -262,7 +795,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_list<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
-        self.expect(Token::LSquare)?;
+        let start = self.expect(Token::LSquare)?.start;
 
         let mut elements = vec![];
         loop {
@@ -270,17 +803,24 @@ impl<'a> Parser<'a> {
                 break;
             }
 
+            if let Err(kind) = ParserErrorKind::check_list_capacity(ty, elements.len()) {
+                // Report the first extra element, whatever its type
+                let extra = self.parse_node()?;
+                return Err(self.error(kind, extra.span()));
+            }
             elements.push(self.parse_value(&ty.list_element_type().unwrap())?);
 
             if let (Token::RSquare, _) = self.expect_any_of(&[Token::Comma, Token::RSquare])? {
                 break;
             }
         }
-        V::make_list(ty, elements).map_err(ParserError::make_value)
+        ParserErrorKind::check_list_length(ty, elements.len())
+            .map_err(|kind| self.error(kind, start..self.pos()))?;
+        V::make_list(ty, elements).map_err(|err| self.make_value_error(err, start))
     }
 
     fn parse_record<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
-        self.expect(Token::LCurly)?;
+        let start = self.expect(Token::LCurly)?.start;
 
         let field_types = ty
             .record_fields()
@@ -300,8 +840,8 @@ impl<'a> Parser<'a> {
                 break;
             }
 
-            let name = self.expect_name(remaining.keys().cloned())?;
-            remaining.remove(name);
+            let (name, _) = self.expect_name(remaining.keys().cloned())?;
+            remaining.shift_remove(name);
             let (idx, ty) = field_types.get(name).unwrap();
 
             self.expect(Token::Colon)?;
@@ -326,18 +866,23 @@ impl<'a> Parser<'a> {
                     Some(val) => val,
                     None if ty.kind() == WasmTypeKind::Option => {
                         // Omitted `option` field; use "none"
-                        V::make_option(ty, None).map_err(ParserError::make_value)?
+                        V::make_option(ty, None).map_err(|err| self.make_value_error(err, start))?
+                    }
+                    None => {
+                        return Err(self.error(
+                            ParserErrorKind::FieldMissing(name.to_string()),
+                            start..self.pos(),
+                        ))
                     }
-                    None => return Err(ParserError::FieldMissing(name.to_string())),
                 };
                 Ok((name.as_ref(), val))
             })
             .collect::<Result<Vec<_>, _>>()?;
-        V::make_record(ty, fields).map_err(ParserError::make_value)
+        V::make_record(ty, fields).map_err(|err| self.make_value_error(err, start))
     }
 
     fn parse_tuple<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
-        self.expect(Token::LParen)?;
+        let start = self.expect(Token::LParen)?.start;
 
         let types = ty.tuple_element_types().collect::<Vec<_>>();
         let len = types.len();
@@ -353,68 +898,80 @@ impl<'a> Parser<'a> {
                 self.expect(Token::Comma)?;
             }
         }
-        V::make_tuple(ty, values).map_err(ParserError::make_value)
+        V::make_tuple(ty, values).map_err(|err| self.make_value_error(err, start))
     }
 
     fn parse_variant<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
-        let name = self.expect_name(ty.variant_cases().map(|(name, _)| name))?;
-        let (case_name, case_ty) = ty
+        let (name, span) = self.expect_name(ty.variant_cases().map(|(name, _)| name))?;
+        let Some((case_name, case_ty)) = ty
             .variant_cases()
             .find(|(case_name, _)| case_name.as_ref() == name)
-            .ok_or_else(|| {
-                ParserError::unexpected_name(ty.variant_cases().map(|(name, _)| name), name)
-            })?;
-        V::make_variant(ty, case_name.as_ref(), self.parse_maybe_payload(case_ty)?)
-            .map_err(ParserError::make_value)
+        else {
+            return Err(self.unexpected_name(ty.variant_cases().map(|(name, _)| name), name, span));
+        };
+        let payload = self.parse_maybe_payload(case_ty)?;
+        V::make_variant(ty, case_name.as_ref(), payload)
+            .map_err(|err| self.make_value_error(err, span.start))
     }
 
     fn parse_enum<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
-        let name = self.expect_name(ty.enum_cases())?;
-        V::make_enum(ty, name).map_err(ParserError::make_value)
+        let (name, span) = self.expect_name(ty.enum_cases())?;
+        V::make_enum(ty, name).map_err(|err| self.make_value_error(err, span.start))
     }
 
     fn parse_option<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
         let some_ty = ty.option_some_type().unwrap();
+        let start = self.peek_start();
         let peek_name = self.peek_name();
         let val = if peek_name.is_some_and(|s| SOME.starts_with(s) || NONE.starts_with(s)) {
             match self.parse_name()? {
-                SOME => self.parse_maybe_payload(Some(some_ty))?,
-                NONE => None,
-                other => {
-                    return Err(ParserError::unexpected_name([SOME, NONE], other));
+                (SOME, _) => self.parse_maybe_payload(Some(some_ty))?,
+                (NONE, _) => None,
+                (other, span) => {
+                    return Err(self.unexpected_name([SOME, NONE], other, span));
                 }
             }
         } else if flattenable(some_ty.kind()) {
             Some(self.parse_value(&some_ty)?)
         } else {
-            let got = self.parse_name()?;
-            return Err(ParserError::unexpected_name([SOME, NONE], got));
+            let (got, span) = self.parse_name()?;
+            return Err(self.unexpected_name([SOME, NONE], got, span));
         };
-        V::make_option(ty, val).map_err(ParserError::make_value)
+        V::make_option(ty, val).map_err(|err| self.make_value_error(err, start))
     }
 
     fn parse_result<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
         let (ok_ty, err_ty) = ty.result_types().unwrap();
+        let start = self.peek_start();
         let peek_name = self.peek_name();
         let val = if peek_name.is_some_and(|s| OK.starts_with(s) || ERR.starts_with(s)) {
             match self.parse_name()? {
-                OK => Ok(self.parse_maybe_payload(ok_ty)?),
-                ERR => Err(self.parse_maybe_payload(err_ty)?),
-                other => {
-                    return Err(ParserError::unexpected_name([OK, ERR], other));
+                (OK, _) => Ok(self.parse_maybe_payload(ok_ty)?),
+                (ERR, _) => Err(self.parse_maybe_payload(err_ty)?),
+                (other, span) => {
+                    return Err(self.unexpected_name([OK, ERR], other, span));
                 }
             }
         } else if ok_ty.is_some() && flattenable(ok_ty.as_ref().unwrap().kind()) {
             Ok(Some(self.parse_value(&ok_ty.unwrap())?))
         } else {
-            let got = self.parse_name()?;
-            return Err(ParserError::unexpected_name([OK, ERR], got));
+            let (got, span) = self.parse_name()?;
+            return Err(self.unexpected_name([OK, ERR], got, span));
         };
-        V::make_result(ty, val).map_err(ParserError::make_value)
+        V::make_result(ty, val).map_err(|err| self.make_value_error(err, start))
+    }
+
+    fn parse_handle<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
+        let label = crate::ty::handle_label(ty);
+        let (_, span) = self.expect_name([label])?;
+        self.expect(Token::LParen)?;
+        let id = self.parse_number(false)?;
+        self.expect(Token::RParen)?;
+        V::make_handle(ty, id).map_err(|err| self.make_value_error(err, span.start))
     }
 
     fn parse_flags<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
-        self.expect(Token::LCurly)?;
+        let start = self.expect(Token::LCurly)?.start;
         let mut names: IndexSet<_> = ty.flags_names().collect();
         let mut flags = Vec::new();
         loop {
@@ -423,25 +980,36 @@ impl<'a> Parser<'a> {
             }
 
             let remaining = names.iter().cloned();
-            let name = self.expect_name(remaining)?;
+            let (name, _) = self.expect_name(remaining)?;
 
-            let flag = names.take(name).unwrap();
+            let flag = names.shift_take(name).unwrap();
             flags.push(flag);
 
             if let (Token::RCurly, _) = self.expect_any_of(&[Token::RCurly, Token::Comma])? {
                 break;
             }
         }
-        V::make_flags(ty, flags.iter().map(Cow::as_ref)).map_err(ParserError::make_value)
+        V::make_flags(ty, flags.iter().map(Cow::as_ref))
+            .map_err(|err| self.make_value_error(err, start))
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token, Span)>, ParserError> {
+        let start = self.tokens.pos();
+        match self.tokens.next_token() {
+            Ok(token) => Ok(token.map(|token| (token, start..self.tokens.pos()))),
+            Err(err) => {
+                let span = self.tokens.error_span(&err, start);
+                Err(self.error(err.into(), span))
+            }
+        }
     }
 
     fn next_non_whitespace(&mut self) -> Result<Option<(Token, Span)>, ParserError> {
         if let Some(peeked) = self.peeked.take() {
             return Some(peeked).transpose();
         }
-        for res in &mut self.tokens {
-            let (token, span) = res?;
-            if token != Token::Whitespace {
+        while let Some((token, span)) = self.next_token()? {
+            if !token.is_insignificant() {
                 return Ok(Some((token, span)));
             }
         }
@@ -458,29 +1026,76 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // Returns the start of the next non-whitespace token, or the current
+    // position if there isn't one.
+    fn peek_start(&mut self) -> usize {
+        match self.peek_next_non_whitespace() {
+            Some((_, span)) => span.start,
+            None => self.pos(),
+        }
+    }
+
+    // Returns the span of the next non-whitespace token, or an empty span at
+    // the current position if there isn't one.
+    fn peek_span(&mut self) -> Span {
+        let start = self.peek_start();
+        match self.peek_next_non_whitespace() {
+            Some((_, span)) => span,
+            None => start..start,
+        }
+    }
+
+    // Returns true if the next tokens are one of the given key tokens and a
+    // colon, as for a named arg.
+    fn peek_entry(&mut self, keys: &[Token]) -> bool {
+        if !self.peek_token().is_some_and(|token| keys.contains(&token)) {
+            return false;
+        }
+        // The tokenizer is left just after the peeked key
+        next_significant_token(self.tokens.clone()) == Some(Token::Colon)
+    }
+
+    // Returns true if the next tokens are the given token and then an entry
+    // as for `peek_entry`, without consuming anything.
+    fn peek_entry_after(&mut self, token: Token, keys: &[Token]) -> bool {
+        if self.peek_token() != Some(token) {
+            return false;
+        }
+        let mut tokens = self.tokens.clone();
+        next_significant_token(tokens.by_ref()).is_some_and(|token| keys.contains(&token))
+            && next_significant_token(tokens) == Some(Token::Colon)
+    }
+
+    fn peek_token(&mut self) -> Option<Token> {
+        self.peek_next_non_whitespace().map(|(token, _)| token)
+    }
+
     fn peek_name(&mut self) -> Option<&str> {
-        self.peek_next_non_whitespace()
-            .and_then(|(token, span)| (token == Token::Name).then(|| {
+        self.peek_next_non_whitespace().and_then(|(token, span)| {
+            (token == Token::Name).then(|| {
                 let name = self.tokens.get_span(span);
                 name.strip_prefix('%').unwrap_or(name)
-            }))
+            })
+        })
     }
 
     fn expect_any_of(&mut self, expected: &[Token]) -> Result<(Token, Span), ParserError> {
-        if let Some((token, span)) = self.next_non_whitespace()? {
-            if expected.contains(&token) {
-                Ok((token, span))
-            } else {
-                Err(ParserError::UnexpectedToken {
+        match self.next_non_whitespace()? {
+            Some((token, span)) if expected.contains(&token) => Ok((token, span)),
+            Some((token, span)) => Err(self.error(
+                ParserErrorKind::UnexpectedToken {
                     expected: expected.to_vec(),
                     got: Some(token),
-                })
-            }
-        } else {
-            Err(ParserError::UnexpectedToken {
-                expected: expected.to_vec(),
-                got: None,
-            })
+                },
+                span,
+            )),
+            None => Err(self.error(
+                ParserErrorKind::UnexpectedToken {
+                    expected: expected.to_vec(),
+                    got: None,
+                },
+                self.end_span(),
+            )),
         }
     }
 
@@ -506,7 +1121,7 @@ impl<'a> Parser<'a> {
     // Parse a character within a char or string literal. Also returns the
     // number of bytes parsed and whether it was an escape.
     fn parse_char_inner(&self, span: Span) -> Result<(char, usize, bool), ParserError> {
-        let span_str = self.tokens.get_span(span);
+        let span_str = self.tokens.get_span(span.clone());
         let mut chars = span_str.chars();
 
         let ch = chars.next().unwrap();
@@ -514,6 +1129,11 @@ impl<'a> Parser<'a> {
             return Ok((ch, ch.len_utf8(), false));
         }
 
+        let invalid_escape = |escape: String| {
+            // Include the leading backslash in the span
+            let end = span.start + 1 + escape.len();
+            self.error(ParserErrorKind::InvalidEscape(escape), span.start..end)
+        };
         match chars.next().unwrap() {
             esc @ ('\'' | '"' | '\\') => Ok((esc, 2, true)),
             'n' => Ok(('\n', 2, true)),
@@ -521,9 +1141,7 @@ impl<'a> Parser<'a> {
             't' => Ok(('\t', 2, true)),
             'u' => {
                 if chars.next() != Some('{') {
-                    return Err(ParserError::InvalidEscape(
-                        span_str.chars().skip(1).take(2).collect(),
-                    ));
+                    return Err(invalid_escape(span_str.chars().skip(1).take(2).collect()));
                 }
                 let mut nibbles = chars.clone().map(|ch| ch.to_digit(16));
                 let mut num_nibbles = 0;
@@ -533,51 +1151,50 @@ impl<'a> Parser<'a> {
                     value <<= 4;
                     value |= nibble;
                     if value > 0x10FFFF {
-                        return Err(ParserError::InvalidEscape(
+                        return Err(invalid_escape(
                             span_str.chars().skip(1).take(2 + num_nibbles + 1).collect(),
                         ));
                     }
                 }
                 if chars.nth(num_nibbles) != Some('}') {
-                    return Err(ParserError::InvalidEscape(
+                    return Err(invalid_escape(
                         span_str.chars().skip(1).take(2 + num_nibbles + 1).collect(),
                     ));
                 }
                 match value.try_into() {
                     Ok(ch) => Ok((ch, 3 + num_nibbles + 1, true)),
-                    Err(_) => Err(ParserError::InvalidEscape(
+                    Err(_) => Err(invalid_escape(
                         span_str.chars().skip(1).take(2 + num_nibbles + 1).collect(),
                     )),
                 }
             }
-            other => Err(ParserError::InvalidEscape(other.to_string())),
+            other => Err(invalid_escape(other.to_string())),
         }
     }
 
     fn expect_name<T: Borrow<str>>(
         &mut self,
         names: impl IntoIterator<Item = T>,
-    ) -> Result<&str, ParserError> {
-        let name = match self.parse_name() {
+    ) -> Result<(&'a str, Span), ParserError> {
+        let (name, span) = match self.parse_name() {
             Ok(name) => name,
-            Err(ParserError::UnexpectedToken { got: None, .. }) => "",
-            Err(other) => return Err(other),
+            Err(err) => match err.kind {
+                ParserErrorKind::UnexpectedToken { got: None, .. } => ("", err.span),
+                _ => return Err(err),
+            },
         };
         let names = names.into_iter().collect::<Vec<_>>();
         if names.iter().any(|n| n.borrow() == name) {
-            Ok(name)
+            Ok((name, span))
         } else {
-            Err(ParserError::unexpected_name(
-                names.iter().map(|n| n.borrow().to_string()),
-                name,
-            ))
+            Err(self.unexpected_name(names.iter().map(|n| n.borrow().to_string()), name, span))
         }
     }
 
-    fn parse_name(&mut self) -> Result<&str, ParserError> {
+    fn parse_name(&mut self) -> Result<(&'a str, Span), ParserError> {
         let span = self.expect(Token::Name)?;
-        let name = self.tokens.get_span(span);
-        Ok(name.strip_prefix('%').unwrap_or(name))
+        let name = self.tokens.get_span(span.clone());
+        Ok((name.strip_prefix('%').unwrap_or(name), span))
     }
 
     fn parse_maybe_payload<V: WasmValue>(
@@ -602,8 +1219,8 @@ impl<'a> Parser<'a> {
     ) -> ParserError {
         // Convert several errors to `UnexpectedEnd` with optional Completion
         let is_unexpected_end = {
-            use ParserError::*;
-            match err {
+            use ParserErrorKind::*;
+            match err.kind {
                 Lex(LexError::UnexpectedEnd) | UnexpectedToken { got: None, .. } => true,
                 MakeValueError(_) | ParseFloat(_) | UnexpectedName { .. } => self.tokens.ended(),
                 _ => false,
@@ -611,22 +1228,161 @@ impl<'a> Parser<'a> {
         };
         if is_unexpected_end {
             let completions = self.completion.then(|| {
-                let prefix = self.tokens.get_span(start..);
-                crate::completion::Completions::new(prefix, &err, ty)
+                let input = self.tokens.get_span(..);
+                crate::completion::Completions::new(input, start, &err.kind, ty)
             });
-            ParserError::UnexpectedEnd {
-                source: Box::new(err),
-                completions,
+            ParserError {
+                kind: ParserErrorKind::UnexpectedEnd {
+                    source: Box::new(err.kind),
+                    completions,
+                },
+                ..err
             }
         } else {
             err
         }
     }
+
+    fn error(&self, kind: ParserErrorKind, span: Span) -> ParserError {
+        let location = self.tokens.location(span.start);
+        ParserError::new(kind, span, location)
+    }
+
+    fn make_value_error(&self, err: impl Display, start: usize) -> ParserError {
+        self.error(ParserErrorKind::make_value(err), start..self.pos())
+    }
+
+    fn unexpected_name<I: Into<String>>(
+        &self,
+        expected: impl IntoIterator<Item = I>,
+        got: impl Into<String>,
+        span: Span,
+    ) -> ParserError {
+        self.error(ParserErrorKind::unexpected_name(expected, got), span)
+    }
+
+    fn end_span(&self) -> Span {
+        let end = self.tokens.input_len();
+        end..end
+    }
+}
+
+/// The result of [`Parser::parse_value_recovering`].
+#[derive(Debug)]
+pub struct Recovered<V> {
+    /// The best-effort partial value, or None if nothing could be recovered.
+    pub value: Option<V>,
+    /// All errors found, in input order.
+    pub errors: Vec<ParserError>,
+}
+
+// Errors collected by a recovering parse.
+#[derive(Debug, Default)]
+pub(crate) struct Recovery {
+    errors: Vec<ParserError>,
+    // Set while returning an error for a value that failed because of
+    // errors already recorded, so it isn't recorded again.
+    recorded: bool,
+}
+
+impl Recovery {
+    pub(crate) fn record(&mut self, err: ParserError) {
+        // Skip errors for values containing an error already recorded
+        let cascading = self
+            .errors
+            .iter()
+            .any(|prev| err.span.start <= prev.span.start && prev.span.end <= err.span.end);
+        if !std::mem::take(&mut self.recorded) && !cascading {
+            self.errors.push(err);
+        }
+    }
+
+    // Marks the given error as caused by errors already recorded.
+    pub(crate) fn recorded(&mut self, err: ParserError) -> ParserError {
+        self.recorded = true;
+        err
+    }
+}
+
+// Returns the next significant token from `tokens`, if there is one.
+fn next_significant_token<'a>(mut tokens: impl BorrowMut<Tokenizer<'a>>) -> Option<Token> {
+    loop {
+        match tokens.borrow_mut().next_token() {
+            Ok(Some(token)) if token.is_insignificant() => (),
+            Ok(token) => return token,
+            Err(_) => return None,
+        }
+    }
+}
+
+fn is_end_of_input(err: &ParserError) -> bool {
+    matches!(
+        err.kind,
+        ParserErrorKind::UnexpectedToken { got: None, .. }
+            | ParserErrorKind::Lex(crate::lex::LexError::UnexpectedEnd)
+    )
+}
+
+fn mixed_args_error() -> ParserErrorKind {
+    ParserErrorKind::ParseParams("named and positional args can't be mixed".into())
+}
+
+fn mixed_results_error() -> ParserErrorKind {
+    ParserErrorKind::ParseParams("labeled and unlabeled results can't be mixed".into())
+}
+
+// A number type that can be parsed from the text of a WAVE number.
+pub(crate) trait ParseNumber: Sized {
+    fn parse_number(s: &str) -> Result<Self, ParserErrorKind>;
+}
+
+macro_rules! impl_parse_number {
+    (int: $($ty:ty),*) => {
+        $(
+            impl ParseNumber for $ty {
+                fn parse_number(s: &str) -> Result<Self, ParserErrorKind> {
+                    Ok(match split_radix(s) {
+                        Some((radix, digits)) => <$ty>::from_str_radix(&digits, radix)?,
+                        None => s.parse()?,
+                    })
+                }
+            }
+        )*
+    };
+    (float: $($ty:ty),*) => {
+        $(
+            impl ParseNumber for $ty {
+                fn parse_number(s: &str) -> Result<Self, ParserErrorKind> {
+                    Ok(s.parse()?)
+                }
+            }
+        )*
+    };
+}
+
+impl_parse_number!(int: i8, i16, i32, i64, i128, u8, u16, u32, u64);
+impl_parse_number!(float: f32, f64);
+
+// Splits a `0x`, `0o` or `0b` integer literal into its radix and its digits,
+// keeping any leading `-` and removing `_` separators.
+pub(crate) fn split_radix(s: &str) -> Option<(u32, String)> {
+    let (sign, unsigned) = match s.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", s),
+    };
+    let radix = match unsigned.get(..2)? {
+        "0x" => 16,
+        "0o" => 8,
+        "0b" => 2,
+        _ => return None,
+    };
+    let digits = unsigned[2..].replace('_', "");
+    Some((radix, format!("{sign}{digits}")))
 }
 
 pub(crate) fn flattenable(kind: WasmTypeKind) -> bool {
     use WasmTypeKind::*;
-    !matches!(kind, Variant | Enum | Option | Result)
+    !matches!(kind, Variant | Enum | Option | Result) && !crate::ty::is_handle(kind)
 }
 
 impl<'a> From<Tokenizer<'a>> for Parser<'a> {
@@ -636,6 +1392,7 @@ impl<'a> From<Tokenizer<'a>> for Parser<'a> {
             peeked: None,
             peeked_pos: 0,
             completion: false,
+            recovery: None,
         }
     }
 }
@@ -646,9 +1403,114 @@ impl<'a> From<Parser<'a>> for Tokenizer<'a> {
     }
 }
 
+/// A line and column location in WAVE-encoded input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Location {
+    /// The 1-based line number.
+    pub line: usize,
+    /// The 1-based column number, counted in chars.
+    pub column: usize,
+}
+
+impl Location {
+    pub(crate) const START: Self = Self { line: 1, column: 1 };
+
+    /// Returns the [`Location`] of the given byte offset in `input`.
+    /// # Panics
+    /// Panics if `offset` is not on a char boundary of `input`.
+    pub fn from_offset(input: &str, offset: usize) -> Self {
+        Self::START.advance(&input[..offset.min(input.len())])
+    }
+
+    /// Returns the location after `text`, starting at `self`.
+    pub(crate) fn advance(mut self, text: &str) -> Self {
+        for ch in text.chars() {
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
 /// A WAVE Parser error.
+///
+/// Every error carries the byte [`span`](Self::span) of the input where it
+/// occurred along with the line and column [`location`](Self::location) of
+/// the start of that span. See [`DisplayParserError`](crate::fmt::DisplayParserError)
+/// for rendering an error along with the offending source line.
 #[derive(Debug, thiserror::Error)]
-pub enum ParserError {
+#[error("{kind} at {location}")]
+pub struct ParserError {
+    kind: ParserErrorKind,
+    span: Span,
+    location: Location,
+}
+
+impl ParserError {
+    pub(crate) fn new(kind: ParserErrorKind, span: Span, location: Location) -> Self {
+        Self {
+            kind,
+            span,
+            location,
+        }
+    }
+
+    /// Returns the kind of this error.
+    pub fn kind(&self) -> &ParserErrorKind {
+        &self.kind
+    }
+
+    /// Returns the kind of this error, consuming the error.
+    pub fn into_kind(self) -> ParserErrorKind {
+        self.kind
+    }
+
+    /// Returns the byte span of the input where this error occurred.
+    pub fn span(&self) -> Span {
+        self.span.clone()
+    }
+
+    /// Returns the line and column of the start of this error's span.
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    // Returns this error for input that was parsed on its own, moved to the
+    // given byte offset and location within a larger input.
+    pub(crate) fn offset(mut self, offset: usize, location: Location) -> Self {
+        self.span = self.span.start + offset..self.span.end + offset;
+        self.location = if self.location.line == 1 {
+            Location {
+                line: location.line,
+                column: location.column + self.location.column - 1,
+            }
+        } else {
+            Location {
+                line: location.line + self.location.line - 1,
+                ..self.location
+            }
+        };
+        if let ParserErrorKind::Lex(LexError::UnexpectedChar(pos)) = &mut self.kind {
+            *pos += offset;
+        }
+        self
+    }
+}
+
+/// The kind of a [`ParserError`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum ParserErrorKind {
     /// Invalid char encoding
     #[error("invalid char: {0}")]
     InvalidChar(&'static str),
@@ -706,14 +1568,61 @@ pub enum ParserError {
     /// Unsupported type (e.g. for a particular [`WasmValue`] impl)
     #[error("unsupported type {0}")]
     Unsupported(String),
+    /// Untyped [`Node`] doesn't match the expected type
+    #[error("expected {expected}, got {got}")]
+    TypeMismatch {
+        /// Expected type
+        expected: String,
+        /// Got node description
+        got: String,
+    },
+    /// Wrong number of elements for a fixed-length list
+    #[error(
+        "expected {expected} list elements, got {}{got}",
+        if *.got > *.expected as usize { "at least " } else { "" }
+    )]
+    ListLength {
+        /// Fixed list length
+        expected: u32,
+        /// Number of elements, counted up to the first extra element if
+        /// there are too many
+        got: usize,
+    },
+    /// Missing or unexpected variant case payload
+    #[error("invalid payload: {0}")]
+    InvalidPayload(String),
+    /// An error from the underlying reader
+    #[error("read failed: {0}")]
+    Io(std::io::Error),
 }
 
-impl ParserError {
-    fn make_value(err: impl Display) -> Self {
+impl ParserErrorKind {
+    pub(crate) fn make_value(err: impl Display) -> Self {
         Self::MakeValueError(err.to_string())
     }
 
-    fn unexpected_name<I: Into<String>>(
+    // Returns a ListLength error if `ty` is a fixed-length list type whose
+    // length isn't `got`.
+    pub(crate) fn check_list_length(ty: &impl WasmType, got: usize) -> Result<(), Self> {
+        match ty.list_length() {
+            Some(expected) if got != expected as usize => Err(Self::ListLength { expected, got }),
+            _ => Ok(()),
+        }
+    }
+
+    // Returns a ListLength error if `ty` is a fixed-length list type that
+    // already has `len` elements, before another is added.
+    pub(crate) fn check_list_capacity(ty: &impl WasmType, len: usize) -> Result<(), Self> {
+        match ty.list_length() {
+            Some(expected) if len >= expected as usize => Err(Self::ListLength {
+                expected,
+                got: len + 1,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub(crate) fn unexpected_name<I: Into<String>>(
         expected: impl IntoIterator<Item = I>,
         got: impl Into<String>,
     ) -> Self {
@@ -786,6 +1695,180 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_radix_integers() {
+        for (input, ty, want) in [
+            ("0xff", Type::U8, Value::make_u8(255)),
+            ("0xFF_FF", Type::U16, Value::make_u16(65535)),
+            ("0o777", Type::U16, Value::make_u16(511)),
+            ("0b1011", Type::U8, Value::make_u8(11)),
+            ("0b_1000_0000", Type::U8, Value::make_u8(128)),
+            ("-0x80", Type::S8, Value::make_s8(-128)),
+            ("0xdead_beef", Type::U32, Value::make_u32(0xdeadbeef)),
+            (
+                "0xffff_ffff_ffff_ffff",
+                Type::U64,
+                Value::make_u64(u64::MAX),
+            ),
+            ("-0b1", Type::S64, Value::make_s64(-1)),
+        ] {
+            assert_eq!(parse_value(input, &ty), want, "for {input:?}");
+        }
+    }
+
+    #[test]
+    fn parse_radix_integer_errors() {
+        for (input, ty) in [
+            ("0x", Type::U8),
+            ("0x_", Type::U8),
+            ("0x100", Type::U8),
+            ("0b102", Type::U8),
+            ("0o8", Type::U8),
+            ("0xg", Type::U8),
+            ("-0x1", Type::U8),
+            ("0X1", Type::U8),
+            ("0x1", Type::FLOAT32),
+            ("1_000", Type::U32),
+        ] {
+            Parser::new(input)
+                .parse_value::<Value>(&ty)
+                .and_then(|_| Parser::new(input).finish())
+                .expect_err(input);
+        }
+    }
+
+    #[test]
+    fn parse_comments() {
+        let ty = Type::record([("a", Type::list(Type::U8)), ("b", Type::STRING)]).unwrap();
+        let want = parse_value(r#"{a: [1, 2], b: "//"}"#, &ty);
+        for input in [
+            "// leading\n{a: [1, 2], b: \"//\"} // trailing",
+            "/* leading */{a: [1, 2], b: \"//\"}/* trailing */",
+            "{ /* a */ a /* b */ : /* c */ [1, /* d */ 2] /* e */, b: \"//\" }",
+            "{\n  a: [\n    1, // one\n    2, // two\n  ],\n  b: \"//\", // three\n}",
+            "{a: [1/**/,2], b: \"//\"}",
+        ] {
+            let mut parser = Parser::new(input);
+            let got = parser
+                .parse_value::<Value>(&ty)
+                .and_then(|val| parser.finish().map(|_| val))
+                .unwrap_or_else(|err| panic!("error decoding {input:?}: {err}"));
+            assert_eq!(got, want, "for {input:?}");
+        }
+
+        let vals: Vec<Value> = Parser::new("( /* x */ 1, // y\n 'z' /* end */ )")
+            .parse_params([&Type::U8, &Type::CHAR, &Type::option(Type::U8)])
+            .unwrap();
+        assert_eq!(vals.len(), 3);
+
+        for input in [
+            "1 /* unterminated",
+            "/* unterminated",
+            "1 / 2",
+            "1 /",
+            "/ 1",
+        ] {
+            let mut parser = Parser::new(input);
+            parser
+                .parse_value::<Value>(&Type::U8)
+                .and_then(|_| parser.finish())
+                .expect_err(input);
+        }
+    }
+
+    #[test]
+    fn parse_value_recovering() {
+        let color = Type::enum_ty(["red", "green"]).unwrap();
+        let ty = Type::list(
+            Type::record([
+                ("id", Type::U8),
+                ("color", color),
+                ("note", Type::option(Type::STRING)),
+            ])
+            .unwrap(),
+        );
+        for (input, want, errors) in [
+            (
+                "[{id: 1, color: red}, {id: 2, color: green}]",
+                "[{id: 1, color: red}, {id: 2, color: green}]",
+                &[][..],
+            ),
+            (
+                "[{id: 1, color: red, bogus: 1}, {id: 2, color: blue}, {id: 300, color: red}]",
+                "[{id: 1, color: red}]",
+                &[(21, 26), (47, 51), (59, 62)][..],
+            ),
+            (
+                "[{id: 1, color: red, note: 5}, {id 2, color: red}, {id: 3, color: green}]",
+                "[{id: 1, color: red}, {id: 3, color: green}]",
+                &[(27, 28), (35, 36)],
+            ),
+            (
+                "[{id: 1 color: red}, {id: 2, color: (red, [)}, {id: 3, color: red} 4]",
+                "[{id: 3, color: red}]",
+                &[(8, 13), (43, 44), (67, 68)],
+            ),
+            (
+                "[{id: 1, color: red}, {id: @, color: red}]",
+                "[{id: 1, color: red}]",
+                &[(27, 28)],
+            ),
+            (
+                "[{id: 1, color: red}, {}, 'x', 1]",
+                "[{id: 1, color: red}]",
+                &[(22, 24), (26, 29), (31, 32)],
+            ),
+            (
+                "[{id: 1, color: red}] x",
+                "[{id: 1, color: red}]",
+                &[(22, 23)],
+            ),
+            ("[{id: 1, color: red}", "[{id: 1, color: red}]", &[(20, 20)]),
+            (
+                "[{id: 1, color: red}, {id: 2",
+                "[{id: 1, color: red}]",
+                &[(28, 28)],
+            ),
+        ] {
+            let recovered = Parser::new(input).parse_value_recovering::<Value>(&ty);
+            let got = recovered
+                .value
+                .map(|val| crate::to_string(&val).unwrap())
+                .unwrap_or_default();
+            assert_eq!(got, want, "for {input:?}: {:?}", recovered.errors);
+            let spans = recovered
+                .errors
+                .iter()
+                .map(|err| (err.span().start, err.span().end))
+                .collect::<Vec<_>>();
+            assert_eq!(spans, errors, "for {input:?}: {:?}", recovered.errors);
+        }
+
+        // Tuples and payloads with errors are left out of their list
+        let ty = Type::list(Type::tuple([Type::U8, Type::option(Type::U8)]).unwrap());
+        let recovered = Parser::new("[(1, some(x)), (2, 3), (4 5), (6, none)]")
+            .parse_value_recovering::<Value>(&ty);
+        let got = crate::to_string(&recovered.value.unwrap()).unwrap();
+        assert_eq!(got, "[(2, some(3)), (6, none)]");
+        assert_eq!(recovered.errors.len(), 2, "{:?}", recovered.errors);
+
+        // Nested values left open at the end of the input are closed
+        let ty = Type::list(Type::list(Type::STRING));
+        let recovered = Parser::new("[[\"a\"], [\"b\", \"c").parse_value_recovering::<Value>(&ty);
+        let got = crate::to_string(&recovered.value.unwrap()).unwrap();
+        assert_eq!(got, "[[\"a\"], [\"b\"]]");
+        // The unterminated string and the missing `]`
+        assert_eq!(recovered.errors.len(), 2, "{:?}", recovered.errors);
+
+        // Flags with errors are left out
+        let ty = Type::flags(["read", "write"]).unwrap();
+        let recovered =
+            Parser::new("{read, exec, read, write}").parse_value_recovering::<Value>(&ty);
+        let got = crate::to_string(&recovered.value.unwrap()).unwrap();
+        assert_eq!(got, "{read, write}");
+        assert_eq!(recovered.errors.len(), 2, "{:?}", recovered.errors);
+    }
+
     #[test]
     fn parse_option_or_result() {
         let ty = Type::option(Type::BOOL);
@@ -918,6 +2001,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unexpected_names_in_declaration_order() {
+        let record = Type::record(["a", "b", "c", "d"].map(|name| (name, Type::U8))).unwrap();
+        let flags = Type::flags(["a", "b", "c", "d"]).unwrap();
+        for (input, ty) in [("{a: 1, x: 2}", record), ("{a, x}", flags)] {
+            let err = Parser::new(input).parse_value::<Value>(&ty).unwrap_err();
+            let ParserErrorKind::UnexpectedName { expected, got } = err.kind() else {
+                panic!("for {input:?}: {err:?}");
+            };
+            assert_eq!(got, "x");
+            // Names already used are left out without reordering the rest
+            assert_eq!(expected, &["b", "c", "d"], "for {input:?}");
+        }
+    }
+
+    #[test]
+    fn parse_fixed_length_lists() {
+        let ty = Type::fixed_list(Type::U8, 3).unwrap();
+        assert_eq!(
+            parse_value("[1, 2, 3,]", &ty),
+            Value::make_list(&ty, [1, 2, 3].map(Value::make_u8)).unwrap()
+        );
+        // Too many elements are reported at the first extra one
+        for (input, got, span) in [
+            ("[]", 0, 0..2),
+            ("[1, 2]", 2, 0..6),
+            ("[1, 2, 3, 4]", 4, 10..11),
+            ("[1, 2, 3, [4], 5, 6]", 4, 10..13),
+        ] {
+            let err = Parser::new(input).parse_value::<Value>(&ty).unwrap_err();
+            assert!(
+                matches!(err.kind(), ParserErrorKind::ListLength { expected: 3, got: n } if *n == got),
+                "for {input:?}: {err:?}"
+            );
+            assert_eq!(err.span(), span, "for {input:?}");
+            // The untyped AST path reports the same error
+            let node = crate::ast::parse(input).unwrap();
+            let err = node.to_wasm_value::<Value>(&ty).unwrap_err();
+            assert_eq!(err.span(), span, "for {input:?}");
+            assert!(matches!(err.kind(), ParserErrorKind::ListLength { .. }));
+        }
+        let err = Parser::new("[1, 2, 3, 4, 5]")
+            .parse_value::<Value>(&ty)
+            .unwrap_err();
+        assert_eq!(
+            err.kind().to_string(),
+            "expected 3 list elements, got at least 4"
+        );
+    }
+
     #[test]
     fn parse_percent_identifiers() {
         let ty = Type::record([("red", Type::S32), ("green", Type::CHAR)]).unwrap();
@@ -970,6 +2103,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_func_args() {
+        let func = crate::value::FuncType::new(
+            [
+                ("a".into(), Type::U8),
+                ("b".into(), Type::option(Type::U8)),
+                ("c".into(), Type::option(Type::BOOL)),
+            ],
+            [],
+        )
+        .unwrap();
+        for (input, expected) in [
+            ("(1)", "(1, none, none)"),
+            ("(1, 2, false)", "(1, some(2), some(false))"),
+            ("(a: 1)", "(1, none, none)"),
+            ("(c: true, a: 1)", "(1, none, some(true))"),
+            (
+                "( /* c */ c : true , b: some(2), a: 1, )",
+                "(1, some(2), some(true))",
+            ),
+        ] {
+            let vals: Vec<Value> = Parser::new(input)
+                .parse_func_args(&func)
+                .unwrap_or_else(|err| panic!("error decoding args {input:?}: {err}"));
+            let tuple = Type::tuple(func.params().collect::<Vec<_>>()).unwrap();
+            let tuple_str = crate::to_string(&Value::make_tuple(&tuple, vals).unwrap()).unwrap();
+            assert_eq!(tuple_str, expected, "for {input:?}");
+        }
+
+        for (input, message) in [
+            ("(c: true)", "missing arg `a`"),
+            ("(a: 1, a: 2)", "duplicate arg `a`"),
+            ("(a: 1, 2)", "named and positional args can't be mixed"),
+            ("(1, b: 2)", "named and positional args can't be mixed"),
+        ] {
+            let err = Parser::new(input)
+                .parse_func_args::<Value, _>(&func)
+                .expect_err(input);
+            let ParserErrorKind::ParseParams(got) = err.kind() else {
+                panic!("for {input:?}: {err:?}");
+            };
+            assert_eq!(got, message, "for {input:?}");
+        }
+        let err = Parser::new("(a: 1, d: 2)")
+            .parse_func_args::<Value, _>(&func)
+            .unwrap_err();
+        assert!(
+            matches!(err.kind(), ParserErrorKind::UnexpectedName { got, .. } if got == "d"),
+            "{err:?}"
+        );
+        assert_eq!(err.span(), 7..8);
+    }
+
+    #[test]
+    fn error_spans_and_locations() {
+        let record_ty = Type::record([("a", Type::U8), ("b", Type::option(Type::U8))]).unwrap();
+        for (input, ty, span, (line, column)) in [
+            ("{a: 256}", &record_ty, 4..7, (1, 5)),
+            ("{\n  a: 1,\n  c: 2,\n}", &record_ty, 12..13, (3, 3)),
+            ("{b: 1}", &record_ty, 5..6, (1, 6)),
+            ("{a: 1} x", &record_ty, 7..8, (1, 8)),
+            ("{a: 1", &record_ty, 5..5, (1, 6)),
+            (r#""ab\x""#, &Type::STRING, 3..5, (1, 4)),
+            (r"['☃', '\u{d800}']", &Type::list(Type::CHAR), 9..17, (1, 8)),
+            ("[1,\n 2 ?]", &Type::list(Type::U8), 7..8, (2, 4)),
+        ] {
+            let err = crate::from_str::<Value>(ty, input).unwrap_err();
+            assert_eq!(err.span(), span, "for {input:?}: {err}");
+            let location = err.location();
+            assert_eq!(
+                (location.line, location.column),
+                (line, column),
+                "for {input:?}"
+            );
+        }
+    }
+
     fn parse_unwrap<V: WasmValue>(input: &str, ty: V::Type) -> V {
         Parser::new(input)
             .parse_value(&ty)
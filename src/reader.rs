@@ -0,0 +1,595 @@
+//! Web Assembly Value Encoding reader.
+
+use std::io::{self, BufRead};
+
+use indexmap::IndexMap;
+
+use crate::{
+    lex::{LexError, Span, Token, Tokenizer},
+    parser::{Location, Parser, ParserError, ParserErrorKind},
+    ty::{WasmType, WasmTypeKind},
+    val::WasmValue,
+};
+
+/// A streaming Web Assembly Value Encoding reader.
+///
+/// Reads from the wrapped `R` reader incrementally, so that large inputs
+/// never need to be held in memory all at once. Lists, records and tuples
+/// are read one element at a time; any other value (including a list in an
+/// option, result or variant payload) is buffered whole and then parsed
+/// with a [`Parser`]. Error spans and locations are relative to the start
+/// of the whole input.
+/// ```
+/// use wasm_wave::{reader::Reader, value::{Type, Value}};
+/// let mut reader = Reader::new("[1, 2, 3] // done\n".as_bytes());
+/// let val: Value = reader.read_value(&Type::list(Type::U8)).unwrap();
+/// reader.finish().unwrap();
+/// assert_eq!(val, Value::from([1u8, 2, 3]));
+/// ```
+pub struct Reader<R> {
+    inner: R,
+    // Input that has been read but not yet discarded
+    buf: String,
+    // The position in `buf` of the next unconsumed input
+    pos: usize,
+    // The byte offset and location of the start of `buf` in the input
+    offset: usize,
+    location: Location,
+    // The most recently computed (offset, location), used to avoid
+    // rescanning `buf` from the start for every location lookup.
+    location_cache: (usize, Location),
+    // The bytes of an incomplete UTF-8 sequence at the end of the input read
+    // so far
+    partial_char: Vec<u8>,
+    eof: bool,
+}
+
+impl<R: BufRead> Reader<R> {
+    /// Returns a new Reader for the given [`std::io::BufRead`].
+    pub fn new(r: R) -> Self {
+        Self {
+            inner: r,
+            buf: String::new(),
+            pos: 0,
+            offset: 0,
+            location: Location::START,
+            location_cache: (0, Location::START),
+            partial_char: vec![],
+            eof: false,
+        }
+    }
+
+    /// Reads a WAVE-encoded value of the given [`WasmType`] into a
+    /// corresponding [`WasmValue`].
+    pub fn read_value<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
+        self.discard_consumed();
+        match ty.kind() {
+            WasmTypeKind::List => self.read_list(ty),
+            WasmTypeKind::Record => self.read_record(ty),
+            WasmTypeKind::Tuple => self.read_tuple(ty),
+            _ => self.read_buffered(ty),
+        }
+    }
+
+    /// Returns an error if we didn't reach the end of the input.
+    pub fn finish(&mut self) -> Result<(), ParserError> {
+        match self.token_at(self.pos)? {
+            Some((token, span)) => Err(self.error(
+                ParserErrorKind::UnexpectedTextAfterValue { got: Some(token) },
+                span,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn read_list<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
+        let start = self.expect(Token::LSquare)?.start;
+        let start = self.mark(start);
+
+        let element_type = ty.list_element_type().unwrap();
+        let mut elements = vec![];
+        loop {
+            if self.maybe_close_delim(Token::RSquare, false)? {
+                break;
+            }
+
+            if let Err(kind) = ParserErrorKind::check_list_capacity(ty, elements.len()) {
+                // Report the first extra element, whatever its type
+                if let Some((_, span)) = self.token_at(self.pos)? {
+                    let end = self.scan_value(span.start)?;
+                    return Err(self.error(kind, span.start..end));
+                }
+            }
+            elements.push(self.read_value(&element_type)?);
+
+            if let (Token::RSquare, _) = self.expect_any_of(&[Token::Comma, Token::RSquare])? {
+                break;
+            }
+        }
+        ParserErrorKind::check_list_length(ty, elements.len())
+            .map_err(|kind| self.error_from(kind, start))?;
+        V::make_list(ty, elements).map_err(|err| self.make_value_error(err, start))
+    }
+
+    fn read_record<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
+        let start = self.expect(Token::LCurly)?.start;
+        let start = self.mark(start);
+
+        let field_types = ty
+            .record_fields()
+            .enumerate()
+            .map(|(idx, (name, ty))| (name, (idx, ty)))
+            .collect::<IndexMap<_, _>>();
+        let mut remaining = field_types
+            .iter()
+            .map(|(name, (_, ty))| (name.as_ref(), ty.kind() == WasmTypeKind::Option))
+            .collect::<IndexMap<_, _>>();
+
+        let mut values = vec![None; field_types.len()];
+        loop {
+            if remaining.values().all(|optional| *optional)
+                && self.maybe_close_delim(Token::RCurly, remaining.is_empty())?
+            {
+                break;
+            }
+
+            let span = self.expect(Token::Name)?;
+            let name = &self.buf[span.clone()];
+            let name = name.strip_prefix('%').unwrap_or(name).to_string();
+            if remaining.shift_remove(name.as_str()).is_none() {
+                let kind = ParserErrorKind::unexpected_name(remaining.keys().copied(), name);
+                return Err(self.error(kind, span));
+            }
+            let (idx, ty) = field_types.get(name.as_str()).unwrap();
+
+            self.expect(Token::Colon)?;
+
+            values[*idx] = Some(self.read_value(ty)?);
+
+            if remaining.values().all(|optional| *optional) {
+                if let (Token::RCurly, _) = self.expect_any_of(&[Token::RCurly, Token::Comma])? {
+                    break;
+                }
+            } else {
+                self.expect(Token::Comma)?;
+            }
+        }
+
+        // Collect fields into correctly-ordered vec
+        let fields = field_types
+            .iter()
+            .zip(values)
+            .map(|((name, (_, ty)), maybe_val)| {
+                let val = match maybe_val {
+                    Some(val) => val,
+                    None if ty.kind() == WasmTypeKind::Option => {
+                        // Omitted `option` field; use "none"
+                        V::make_option(ty, None).map_err(|err| self.make_value_error(err, start))?
+                    }
+                    None => {
+                        let kind = ParserErrorKind::FieldMissing(name.to_string());
+                        return Err(self.error_from(kind, start));
+                    }
+                };
+                Ok((name.as_ref(), val))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        V::make_record(ty, fields).map_err(|err| self.make_value_error(err, start))
+    }
+
+    fn read_tuple<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
+        let start = self.expect(Token::LParen)?.start;
+        let start = self.mark(start);
+
+        let types = ty.tuple_element_types().collect::<Vec<_>>();
+        let len = types.len();
+        let mut values = Vec::with_capacity(len);
+        for ty in types.into_iter() {
+            values.push(self.read_value(&ty)?);
+
+            if values.len() == len {
+                if let (Token::Comma, _) = self.expect_any_of(&[Token::RParen, Token::Comma])? {
+                    self.expect(Token::RParen)?;
+                }
+            } else {
+                self.expect(Token::Comma)?;
+            }
+        }
+        V::make_tuple(ty, values).map_err(|err| self.make_value_error(err, start))
+    }
+
+    // Buffers the whole of the next value and parses it with a `Parser`.
+    fn read_buffered<V: WasmValue>(&mut self, ty: &V::Type) -> Result<V, ParserError> {
+        let start = match self.token_at(self.pos)? {
+            Some((_, span)) => span.start,
+            None => self.buf.len(),
+        };
+        let end = self.scan_value(start)?;
+        let res = {
+            let mut parser = Parser::new(&self.buf[start..end]);
+            parser.parse_value(ty).and_then(|val| {
+                parser.finish()?;
+                Ok(val)
+            })
+        };
+        match res {
+            Ok(val) => {
+                self.pos = end;
+                Ok(val)
+            }
+            Err(err) => {
+                let (offset, location) = self.mark(start);
+                Err(err.offset(offset, location))
+            }
+        }
+    }
+
+    // Returns the end of the value starting at `start`, reading ahead as
+    // needed. The value is only delimited here; it is validated by parsing.
+    fn scan_value(&mut self, start: usize) -> Result<usize, ParserError> {
+        let mut end = start;
+        let mut depth = 0usize;
+        loop {
+            let (token, span) = match self.token_at(end) {
+                Ok(Some(next)) => next,
+                Ok(None) => break,
+                Err(err) if matches!(err.kind(), ParserErrorKind::Io(_)) => return Err(err),
+                // Other errors are left for the parser, to be reported in
+                // input order
+                Err(err) => {
+                    end = err.span().end - self.offset;
+                    break;
+                }
+            };
+            end = span.end;
+            match token {
+                Token::LParen | Token::LSquare | Token::LCurly => depth += 1,
+                Token::RParen | Token::RSquare | Token::RCurly if depth > 0 => depth -= 1,
+                // Include the number or keyword following a sign
+                Token::Dash if depth == 0 => continue,
+                // Include any payload following a case label
+                Token::Name if depth == 0 => {
+                    if let Some((Token::LParen, _)) = self.token_at(end)? {
+                        continue;
+                    }
+                }
+                _ => (),
+            }
+            if depth == 0 {
+                break;
+            }
+        }
+        Ok(end)
+    }
+
+    // Returns the next significant token at or after `pos` in `buf`, reading
+    // more input as needed to be sure that the token is complete.
+    fn token_at(&mut self, mut pos: usize) -> Result<Option<(Token, Span)>, ParserError> {
+        loop {
+            let rest = &self.buf[pos..];
+            let mut tokens = Tokenizer::new(rest);
+            let res = tokens.next_token();
+            let len = tokens.pos();
+            // Whether the token might continue past the input read so far
+            let truncated = !self.eof
+                && match &res {
+                    Ok(Some(Token::Name | Token::Number)) => len == rest.len(),
+                    Ok(Some(Token::Comment)) => len == rest.len() && rest.starts_with("//"),
+                    Ok(Some(_)) => false,
+                    Ok(None) | Err(LexError::UnexpectedEnd) => true,
+                    Err(LexError::UnexpectedChar(idx)) => &rest[*idx..] == "/",
+                };
+            if truncated {
+                self.fill(rest.len())?;
+                continue;
+            }
+            match res {
+                Ok(Some(token)) if token.is_insignificant() => pos += len,
+                Ok(Some(token)) => return Ok(Some((token, pos..pos + len))),
+                Ok(None) => return Ok(None),
+                Err(err) => {
+                    let span = tokens.error_span(&err, 0);
+                    let err = match err {
+                        LexError::UnexpectedChar(idx) => {
+                            LexError::UnexpectedChar(self.offset + pos + idx)
+                        }
+                        err => err,
+                    };
+                    return Err(self.error(err.into(), pos + span.start..pos + span.end));
+                }
+            }
+        }
+    }
+
+    fn expect_any_of(&mut self, expected: &[Token]) -> Result<(Token, Span), ParserError> {
+        match self.token_at(self.pos)? {
+            Some((token, span)) if expected.contains(&token) => {
+                self.pos = span.end;
+                Ok((token, span))
+            }
+            got => {
+                let end = self.buf.len();
+                let span = got.as_ref().map_or(end..end, |(_, span)| span.clone());
+                let kind = ParserErrorKind::UnexpectedToken {
+                    expected: expected.to_vec(),
+                    got: got.map(|(token, _)| token),
+                };
+                Err(self.error(kind, span))
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<Span, ParserError> {
+        let (_, span) = self.expect_any_of(&[expected])?;
+        Ok(span)
+    }
+
+    fn maybe_close_delim(&mut self, close: Token, must_close: bool) -> Result<bool, ParserError> {
+        if must_close {
+            self.expect(close)?;
+            return Ok(true);
+        }
+        if let Some((token, span)) = self.token_at(self.pos)? {
+            if token == close {
+                self.pos = span.end;
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // Reads at least `min` more bytes of input, or up to the end of input.
+    // Reading at least as much as is already pending keeps rescanning long
+    // tokens linear overall.
+    fn fill(&mut self, min: usize) -> Result<(), ParserError> {
+        let mut read = 0;
+        while read < min.max(1) && !self.eof {
+            let chunk = match self.inner.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(self.io_error(err)),
+            };
+            if chunk.is_empty() {
+                self.eof = true;
+                if !self.partial_char.is_empty() {
+                    return Err(self.invalid_utf8_error());
+                }
+                break;
+            }
+            let len = chunk.len();
+            self.partial_char.extend_from_slice(chunk);
+            self.inner.consume(len);
+            read += len;
+
+            let valid = match std::str::from_utf8(&self.partial_char) {
+                Ok(s) => s.len(),
+                Err(err) if err.error_len().is_none() => err.valid_up_to(),
+                Err(_) => return Err(self.invalid_utf8_error()),
+            };
+            let valid = std::str::from_utf8(&self.partial_char[..valid]).unwrap();
+            self.buf.push_str(valid);
+            self.partial_char.drain(..valid.len());
+        }
+        Ok(())
+    }
+
+    // Drops consumed input from `buf` once it makes up at least half of it,
+    // so the cost of moving the rest stays proportional to the input read.
+    fn discard_consumed(&mut self) {
+        if self.pos < self.buf.len() / 2 {
+            return;
+        }
+        (self.offset, self.location) = self.mark(self.pos);
+        self.buf.drain(..self.pos);
+        self.pos = 0;
+    }
+
+    // Returns the offset in the input and location of the given position in
+    // `buf`.
+    fn mark(&mut self, pos: usize) -> (usize, Location) {
+        let offset = self.offset + pos;
+        let (cached_offset, cached) = self.location_cache;
+        let location = if cached_offset <= offset {
+            cached.advance(&self.buf[cached_offset - self.offset..pos])
+        } else {
+            self.location.advance(&self.buf[..pos])
+        };
+        self.location_cache = (offset, location);
+        (offset, location)
+    }
+
+    // Returns an error for the given span of `buf`.
+    fn error(&mut self, kind: ParserErrorKind, span: Span) -> ParserError {
+        let (start, location) = self.mark(span.start);
+        let kind = match kind {
+            ParserErrorKind::Lex(LexError::UnexpectedEnd)
+            | ParserErrorKind::UnexpectedToken { got: None, .. } => {
+                ParserErrorKind::UnexpectedEnd {
+                    source: Box::new(kind),
+                    completions: None,
+                }
+            }
+            kind => kind,
+        };
+        ParserError::new(kind, start..self.offset + span.end, location)
+    }
+
+    // Returns an error for the input from `start` up to the current position.
+    fn error_from(&mut self, kind: ParserErrorKind, start: (usize, Location)) -> ParserError {
+        let (offset, location) = start;
+        ParserError::new(kind, offset..self.offset + self.pos, location)
+    }
+
+    fn make_value_error(
+        &mut self,
+        err: impl std::fmt::Display,
+        start: (usize, Location),
+    ) -> ParserError {
+        self.error_from(ParserErrorKind::make_value(err), start)
+    }
+
+    fn io_error(&mut self, err: io::Error) -> ParserError {
+        let end = self.buf.len();
+        self.error(ParserErrorKind::Io(err), end..end)
+    }
+
+    fn invalid_utf8_error(&mut self) -> ParserError {
+        let err = io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        );
+        self.io_error(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Read};
+
+    use super::*;
+    use crate::value::{Type, Value};
+
+    // Reads one byte at a time, to split tokens and chars across reads.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = *first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    fn trickle_reader(input: &[u8]) -> Reader<BufReader<Trickle<'_>>> {
+        Reader::new(BufReader::with_capacity(1, Trickle(input)))
+    }
+
+    fn read_value(input: &str, ty: &Type) -> Result<Value, ParserError> {
+        let mut reader = trickle_reader(input.as_bytes());
+        let val = reader.read_value(ty)?;
+        reader.finish()?;
+        Ok(val)
+    }
+
+    #[test]
+    fn reads_like_parser() {
+        let record = Type::record([
+            ("id", Type::U32),
+            ("name", Type::STRING),
+            ("tags", Type::list(Type::CHAR)),
+            ("note", Type::option(Type::list(Type::S8))),
+        ])
+        .unwrap();
+        let pair = Type::tuple([Type::FLOAT64, Type::result(Some(Type::BOOL), None)]).unwrap();
+        let flags = Type::flags(["read", "write"]).unwrap();
+        for (input, ty) in [
+            ("[]", Type::list(record.clone())),
+            (
+                "[{id: 0xff, name: \"👋 \\\"hi\\\"\", tags: ['☃', '\\n']}, // first\n\
+                 {tags: [], name: \"\", id: 2, note: [-1, -0x80,]}, /* last */]",
+                Type::list(record.clone()),
+            ),
+            (
+                "[(-inf, true), (1.5e3, err), (nan, ok(false))]",
+                Type::list(pair.clone()),
+            ),
+            ("[{read, write}, {}, {write}]", Type::list(flags.clone())),
+            ("{id: 1, name: \"x\", tags: ['y']}", record.clone()),
+            (
+                "/* a */ some([1, 2]) // b",
+                Type::option(Type::list(Type::S8)),
+            ),
+            ("\"multi\nline\"", Type::STRING),
+            // Errors
+            ("[{id: 1, name: \"x\"}]", Type::list(record.clone())),
+            (
+                "[{id: 1, name: \"x\", tags: [], bogus: 1}]",
+                Type::list(record.clone()),
+            ),
+            ("[(1, true),\n (2, maybe)]", Type::list(pair.clone())),
+            ("[(1, true), (2, ok(true)) 3]", Type::list(pair.clone())),
+            ("[{read}, {exec}]", Type::list(flags.clone())),
+            ("[1, 2,\n 300]", Type::list(Type::U8)),
+            ("[1, 2", Type::list(Type::U8)),
+            ("[1, 2] 3", Type::list(Type::U8)),
+            ("[1, @]", Type::list(Type::U8)),
+            ("[300, @]", Type::list(Type::U8)),
+            ("some([300, @])", Type::option(Type::list(Type::U8))),
+            ("[1] /* 2", Type::list(Type::U8)),
+            ("\"abc", Type::STRING),
+            ("[1, 2]", Type::fixed_list(Type::U8, 3).unwrap()),
+            ("[1, 2, 3, [4], 5]", Type::fixed_list(Type::U8, 3).unwrap()),
+        ] {
+            let want = crate::from_str::<Value>(&ty, input);
+            let got = read_value(input, &ty);
+            match (got, want) {
+                (Ok(got), Ok(want)) => {
+                    // Compared as strings as `nan != nan`
+                    let got = crate::to_string(&got).unwrap();
+                    assert_eq!(got, crate::to_string(&want).unwrap(), "for {input:?}");
+                }
+                (Err(got), Err(want)) => {
+                    assert_eq!(got.span(), want.span(), "for {input:?}: {got}");
+                    assert_eq!(got.location(), want.location(), "for {input:?}: {got}");
+                }
+                (got, want) => panic!("for {input:?}: got {got:?}, want {want:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn reads_multiple_values() {
+        let mut reader = trickle_reader(b"[1, 2] 'x'\n\"y\"");
+        let val: Value = reader.read_value(&Type::list(Type::U8)).unwrap();
+        assert_eq!(val, Value::from([1u8, 2]));
+        let val: Value = reader.read_value(&Type::CHAR).unwrap();
+        assert_eq!(val, Value::from('x'));
+        let val: Value = reader.read_value(&Type::STRING).unwrap();
+        assert_eq!(val, Value::from("y"));
+        reader.finish().unwrap();
+    }
+
+    #[test]
+    fn discards_consumed_input() {
+        let input = format!("[{}]", vec!["12345"; 10_000].join(", "));
+        let mut reader = trickle_reader(input.as_bytes());
+        let val: Value = reader.read_value(&Type::list(Type::U32)).unwrap();
+        assert_eq!(val.unwrap_list().count(), 10_000);
+        assert!(
+            reader.buf.len() < 100,
+            "buffered {} bytes",
+            reader.buf.len()
+        );
+    }
+
+    #[test]
+    fn buffers_one_element_at_a_time() {
+        let record = Type::record([("id", Type::U32), ("name", Type::STRING)]).unwrap();
+        let element = r#"{id: 123456, name: "a record in a long list"}"#;
+        let input = format!("[{}]", vec![element; 20_000].join(",\n"));
+        assert!(input.len() > 900_000);
+        let mut reader = Reader::new(BufReader::new(input.as_bytes()));
+        let val: Value = reader.read_value(&Type::list(record)).unwrap();
+        assert_eq!(val.unwrap_list().count(), 20_000);
+        assert!(
+            reader.buf.capacity() < 64 * 1024,
+            "buffer grew to {} bytes",
+            reader.buf.capacity()
+        );
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        let err = read_value_bytes(b"\"ab\xffc\"").unwrap_err();
+        assert!(matches!(err.kind(), ParserErrorKind::Io(_)), "{err:?}");
+        let err = read_value_bytes(b"\"ab\xe2\x98").unwrap_err();
+        assert!(matches!(err.kind(), ParserErrorKind::Io(_)), "{err:?}");
+    }
+
+    fn read_value_bytes(input: &[u8]) -> Result<Value, ParserError> {
+        trickle_reader(input).read_value(&Type::STRING)
+    }
+}
@@ -27,6 +27,11 @@ pub enum WasmTypeKind {
     Option,
     Result,
     Flags,
+    Own,
+    Borrow,
+    Stream,
+    Future,
+    ErrorContext,
     #[doc(hidden)]
     Unsupported,
 }
@@ -55,6 +60,11 @@ impl std::fmt::Display for WasmTypeKind {
             WasmTypeKind::Option => "option",
             WasmTypeKind::Result => "result",
             WasmTypeKind::Flags => "flags",
+            WasmTypeKind::Own => "own",
+            WasmTypeKind::Borrow => "borrow",
+            WasmTypeKind::Stream => "stream",
+            WasmTypeKind::Future => "future",
+            WasmTypeKind::ErrorContext => "error-context",
             WasmTypeKind::Unsupported => "<<UNSUPPORTED>>",
         })
     }
@@ -70,17 +80,29 @@ pub trait WasmType: Clone + Sized {
     /// Returns the [`WasmTypeKind`] of this type.
     fn kind(&self) -> WasmTypeKind;
 
+    /// Returns the name of this type, if it is a named type, e.g. from a WIT
+    /// type definition. The default implementation returns `None`.
+    fn type_name(&self) -> Option<Cow<'_, str>> {
+        None
+    }
+
     /// Returns the list element type or `None` if `self` is not a list type.
     /// # Panics
     /// Panics if the type is not implemented (the trait default).
     fn list_element_type(&self) -> Option<Self> {
         unimplemented!()
     }
+    /// Returns the length of a fixed-length list type or `None` if `self` is
+    /// not a fixed-length list type. The default implementation returns
+    /// `None`.
+    fn list_length(&self) -> Option<u32> {
+        None
+    }
     /// Returns an iterator of the record's field names and Types. The
     /// iterator will be empty iff `self` is not a record type.
     /// # Panics
     /// Panics if the type is not implemented (the trait default).
-    fn record_fields(&self) -> Box<dyn Iterator<Item = (Cow<str>, Self)> + '_> {
+    fn record_fields(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, Self)> + '_> {
         unimplemented!()
     }
     /// Returns an iterator of the tuple's field Types. The iterator will be
@@ -94,14 +116,14 @@ pub trait WasmType: Clone + Sized {
     /// Types. The iterator will be empty iff `self` is not a tuple type.
     /// # Panics
     /// Panics if the type is not implemented (the trait default).
-    fn variant_cases(&self) -> Box<dyn Iterator<Item = (Cow<str>, Option<Self>)> + '_> {
+    fn variant_cases(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, Option<Self>)> + '_> {
         unimplemented!()
     }
     /// Returns an iterator of the enum's case names. The iterator will be
     /// empty iff `self` is not an enum type.
     /// # Panics
     /// Panics if the type is not implemented (the trait default).
-    fn enum_cases(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn enum_cases(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         unimplemented!()
     }
     /// Returns the option's "some" type or `None` if `self` is not an option type.
@@ -121,11 +143,49 @@ pub trait WasmType: Clone + Sized {
     /// `self` is not a flags type.
     /// # Panics
     /// Panics if the type is not implemented (the trait default).
-    fn flags_names(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn flags_names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
+        unimplemented!()
+    }
+    /// Returns the name of the resource of an own or borrow handle type or
+    /// `None` if `self` is not a handle type.
+    /// # Panics
+    /// Panics if the type is not implemented (the trait default).
+    fn resource_name(&self) -> Option<Cow<'_, str>> {
+        unimplemented!()
+    }
+    /// Returns the stream's optional element type or `None` if `self` is not
+    /// a stream type.
+    /// # Panics
+    /// Panics if the type is not implemented (the trait default).
+    fn stream_element_type(&self) -> Option<Option<Self>> {
+        unimplemented!()
+    }
+    /// Returns the future's optional payload type or `None` if `self` is not
+    /// a future type.
+    /// # Panics
+    /// Panics if the type is not implemented (the trait default).
+    fn future_payload_type(&self) -> Option<Option<Self>> {
         unimplemented!()
     }
 }
 
+/// Returns true if values of the given kind are opaque handles, made with
+/// [`WasmValue::make_handle`](crate::WasmValue::make_handle).
+pub(crate) fn is_handle(kind: WasmTypeKind) -> bool {
+    use WasmTypeKind::*;
+    matches!(kind, Own | Borrow | Stream | Future | ErrorContext)
+}
+
+/// Returns the label that WAVE-encoded handle values of the given type start
+/// with: the resource name of an own or borrow handle, or else the kind name,
+/// e.g. `stream`.
+pub(crate) fn handle_label<T: WasmType>(ty: &T) -> Cow<'_, str> {
+    match ty.kind() {
+        WasmTypeKind::Own | WasmTypeKind::Borrow => ty.resource_name().unwrap(),
+        kind => kind.to_string().into(),
+    }
+}
+
 macro_rules! maybe_unwrap {
     ($ty:expr, $case:path) => {
         match $ty {
@@ -7,7 +7,13 @@ pub fn from_wasm_type(ty: &impl WasmType) -> Option<Type> {
         return Some(ty);
     }
     Some(match ty.kind() {
-        WasmTypeKind::List => Type::list(from_wasm_type(&ty.list_element_type()?)?),
+        WasmTypeKind::List => {
+            let element_type = from_wasm_type(&ty.list_element_type()?)?;
+            match ty.list_length() {
+                Some(length) => Type::fixed_list(element_type, length)?,
+                None => Type::list(element_type),
+            }
+        }
         WasmTypeKind::Record => Type::record(
             ty.record_fields()
                 .map(|(name, ty)| Some((name, from_wasm_type(&ty)?)))
@@ -30,6 +36,10 @@ pub fn from_wasm_type(ty: &impl WasmType) -> Option<Type> {
             Type::result(from_optional_wasm_type(ok)?, from_optional_wasm_type(err)?)
         }
         WasmTypeKind::Flags => Type::flags(ty.flags_names())?,
+        WasmTypeKind::Own => Type::own(ty.resource_name()?),
+        WasmTypeKind::Borrow => Type::borrow(ty.resource_name()?),
+        WasmTypeKind::Stream => Type::stream(from_optional_wasm_type(ty.stream_element_type()?)?),
+        WasmTypeKind::Future => Type::future(from_optional_wasm_type(ty.future_payload_type()?)?),
         _ => return None,
     })
 }
@@ -91,7 +101,7 @@ impl From<String> for Value {
     }
 }
 
-impl<'a> ValueTyped for &'a str {
+impl ValueTyped for &str {
     fn value_type() -> Type {
         String::value_type()
     }
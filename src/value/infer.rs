@@ -0,0 +1,725 @@
+use indexmap::{IndexMap, IndexSet};
+
+use super::Type;
+use crate::{
+    ast::{self, Node, NodeKind},
+    parser::{self, ParseNumber, ParserError},
+    WasmType, WasmTypeKind,
+};
+
+/// Infers a [`Type`] from one or more WAVE-encoded samples of the same type.
+///
+/// The inferred type is the narrowest one that accepts every sample:
+/// - Integers use the narrowest unsigned or signed type covering all samples;
+///   numbers with a fraction or exponent (or `inf` / `nan`) make `float64`.
+/// - `{...}` with labeled values is a record; a field missing from some
+///   samples or set to `none` makes it an `option`.
+/// - `{...}` with bare labels is a flags.
+/// - `true` / `false` is a bool; `some` / `none` is an option; `ok` / `err`
+///   is a result; other labels are an enum, or a variant if any has a payload.
+///
+/// Anything the samples don't decide is reported in
+/// [`InferredType::undecided`].
+/// ```
+/// use wasm_wave::value::{infer_type, Type};
+/// # fn main() -> Result<(), wasm_wave::value::InferError> {
+/// let inferred = infer_type(["{id: 1, name: \"a\"}", "{id: 300}"])?;
+/// let expected = Type::record([("id", Type::U16), ("name", Type::option(Type::STRING))]);
+/// assert_eq!(inferred.ty(), expected.as_ref());
+///
+/// let inferred = infer_type(["north"])?;
+/// assert_eq!(inferred.ty(), Type::enum_ty(["north"]).as_ref());
+/// assert_eq!(inferred.undecided().len(), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub fn infer_type<'a>(
+    samples: impl IntoIterator<Item = &'a str>,
+) -> Result<InferredType, InferError> {
+    let mut shape = None;
+    for (index, sample) in samples.into_iter().enumerate() {
+        let node = ast::parse(sample).map_err(|source| InferError::Parse { index, source })?;
+        let mut cx = Context {
+            index,
+            path: ROOT.to_string(),
+        };
+        let sample_shape = cx.shape(&node)?;
+        shape = Some(match shape {
+            None => sample_shape,
+            Some(shape) => cx.merge(shape, sample_shape)?,
+        });
+    }
+    let shape = shape.ok_or(InferError::NoSamples)?;
+    let mut undecided = vec![];
+    let ty = resolve(shape, &mut ROOT.to_string(), &mut undecided);
+    Ok(InferredType { ty, undecided })
+}
+
+/// The result of [`infer_type`].
+#[derive(Clone, Debug)]
+pub struct InferredType {
+    ty: Option<Type>,
+    undecided: Vec<Undecided>,
+}
+
+impl InferredType {
+    /// Returns the inferred type, or None if the samples don't contain enough
+    /// information to infer some part of it (e.g. a list that is always
+    /// empty). The reason is reported in [`Self::undecided`].
+    pub fn ty(&self) -> Option<&Type> {
+        self.ty.as_ref()
+    }
+
+    /// Returns the inferred type, if any. See [`Self::ty`].
+    pub fn into_type(self) -> Option<Type> {
+        self.ty
+    }
+
+    /// Returns the parts of the type that the samples didn't decide. Parts
+    /// that still have a type use the most likely one.
+    pub fn undecided(&self) -> &[Undecided] {
+        &self.undecided
+    }
+}
+
+/// A part of an [`InferredType`] that the samples didn't decide.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Undecided {
+    path: String,
+    reason: String,
+}
+
+impl Undecided {
+    /// Returns the path to the undecided part, e.g. `value.field[].0`.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns a description of what was undecided.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl std::fmt::Display for Undecided {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.reason)
+    }
+}
+
+/// An error from [`infer_type`].
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum InferError {
+    /// No samples were given
+    #[error("no samples")]
+    NoSamples,
+    /// A sample could not be parsed
+    #[error("sample {index}: {source}")]
+    Parse {
+        /// The index of the sample
+        index: usize,
+        /// The parser error
+        source: ParserError,
+    },
+    /// A sample is inconsistent with earlier samples (or itself)
+    #[error("sample {index}: at {path}: expected {expected}, got {got}")]
+    Conflict {
+        /// The index of the sample
+        index: usize,
+        /// The path to the conflicting value
+        path: String,
+        /// A description of the shape seen before
+        expected: String,
+        /// A description of the conflicting shape
+        got: String,
+    },
+}
+
+const ROOT: &str = "value";
+
+// The shape of the samples seen at one position.
+#[derive(Debug)]
+enum Shape {
+    // Nothing seen, e.g. the elements of an empty list
+    Unknown,
+    Int { min: i128, max: i128 },
+    Float,
+    Char,
+    String,
+    List(Box<Shape>),
+    Tuple(Vec<Shape>),
+    // `{}`, seen `count` times
+    EmptyBraces { count: usize },
+    Record(Record),
+    Flags(IndexSet<Box<str>>),
+    // Labels with optional payloads; bools, variants, enums, options, results
+    Cases(IndexMap<Box<str>, Option<Shape>>),
+}
+
+#[derive(Debug)]
+struct Record {
+    fields: IndexMap<Box<str>, Field>,
+    // The number of times a record was seen
+    count: usize,
+}
+
+#[derive(Debug)]
+struct Field {
+    shape: Shape,
+    count: usize,
+}
+
+impl Shape {
+    fn describe(&self) -> String {
+        match self {
+            Self::Unknown => "nothing".into(),
+            Self::Int { .. } => "integer".into(),
+            Self::Float => "float".into(),
+            Self::Char => "char".into(),
+            Self::String => "string".into(),
+            Self::List(_) => "list".into(),
+            Self::Tuple(elements) => format!("tuple of {} values", elements.len()),
+            Self::EmptyBraces { .. } => "`{}`".into(),
+            Self::Record(_) => "record".into(),
+            Self::Flags(_) => "flags".into(),
+            Self::Cases(cases) => {
+                let names = cases.keys().map(|name| format!("`{name}`"));
+                format!("labels {}", names.collect::<Vec<_>>().join(", "))
+            }
+        }
+    }
+
+    // Returns true if this is only `inf` and/or `nan` labels.
+    fn is_float_labels(&self) -> bool {
+        matches!(self, Self::Cases(cases) if cases.iter().all(|(name, payload)| {
+            matches!(name.as_ref(), "inf" | "nan") && payload.is_none()
+        }))
+    }
+}
+
+// Returns the label that a flat (unlabeled) payload belongs to, if `cases`
+// look like an option or result.
+fn flat_case(cases: &IndexMap<Box<str>, Option<Shape>>) -> Option<&'static str> {
+    let only = |allowed: [&str; 2]| cases.keys().all(|name| allowed.contains(&name.as_ref()));
+    if only([parser::SOME, parser::NONE]) && !matches!(cases.get(parser::NONE), Some(Some(_))) {
+        Some(parser::SOME)
+    } else if only([parser::OK, parser::ERR]) {
+        Some(parser::OK)
+    } else {
+        None
+    }
+}
+
+struct Context {
+    index: usize,
+    path: String,
+}
+
+impl Context {
+    fn shape(&mut self, node: &Node) -> Result<Shape, InferError> {
+        Ok(match node.kind() {
+            NodeKind::Number(number) => match i128::parse_number(number) {
+                Ok(n) => Shape::Int { min: n, max: n },
+                Err(_) => Shape::Float,
+            },
+            NodeKind::Char(_) => Shape::Char,
+            NodeKind::String(_) => Shape::String,
+            NodeKind::VariantCase { label, payload } => {
+                let payload = match payload {
+                    Some(node) => Some(self.nested(label.name(), |cx| cx.shape(node))?),
+                    None => None,
+                };
+                Shape::Cases([(label.name().into(), payload)].into_iter().collect())
+            }
+            NodeKind::Tuple(nodes) => Shape::Tuple(
+                nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, node)| self.nested(&idx.to_string(), |cx| cx.shape(node)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            NodeKind::List(nodes) => {
+                self.path.push_str("[]");
+                let mut element = Shape::Unknown;
+                for node in nodes {
+                    let shape = self.shape(node)?;
+                    element = self.merge(element, shape)?;
+                }
+                self.path.truncate(self.path.len() - 2);
+                Shape::List(Box::new(element))
+            }
+            // Not produced by `ast::parse`
+            NodeKind::Error => Shape::Unknown,
+            NodeKind::Flags(labels) if labels.is_empty() => Shape::EmptyBraces { count: 1 },
+            NodeKind::Flags(labels) => {
+                Shape::Flags(labels.iter().map(|label| label.name().into()).collect())
+            }
+            NodeKind::Record(fields) => {
+                let mut record = Record {
+                    fields: IndexMap::new(),
+                    count: 1,
+                };
+                for (label, node) in fields {
+                    if record.fields.contains_key(label.name()) {
+                        return Err(self.conflict(
+                            "unique field names".into(),
+                            format!("duplicate field `{}`", label.name()),
+                        ));
+                    }
+                    let shape = self.nested(label.name(), |cx| cx.shape(node))?;
+                    record
+                        .fields
+                        .insert(label.name().into(), Field { shape, count: 1 });
+                }
+                Shape::Record(record)
+            }
+        })
+    }
+
+    fn merge(&mut self, a: Shape, b: Shape) -> Result<Shape, InferError> {
+        Ok(match (a, b) {
+            (Shape::Unknown, other) | (other, Shape::Unknown) => other,
+            (
+                Shape::Int { min, max },
+                Shape::Int {
+                    min: min2,
+                    max: max2,
+                },
+            ) => Shape::Int {
+                min: min.min(min2),
+                max: max.max(max2),
+            },
+            (Shape::Int { .. } | Shape::Float, Shape::Int { .. } | Shape::Float) => Shape::Float,
+            (Shape::Char, Shape::Char) => Shape::Char,
+            (Shape::String, Shape::String) => Shape::String,
+            (Shape::List(a), Shape::List(b)) => {
+                self.path.push_str("[]");
+                let element = self.merge(*a, *b)?;
+                self.path.truncate(self.path.len() - 2);
+                Shape::List(Box::new(element))
+            }
+            (Shape::Tuple(a), Shape::Tuple(b)) if a.len() == b.len() => Shape::Tuple(
+                a.into_iter()
+                    .zip(b)
+                    .enumerate()
+                    .map(|(idx, (a, b))| self.nested(&idx.to_string(), |cx| cx.merge(a, b)))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (Shape::EmptyBraces { count }, Shape::EmptyBraces { count: count2 }) => {
+                Shape::EmptyBraces {
+                    count: count + count2,
+                }
+            }
+            (Shape::EmptyBraces { count }, Shape::Record(mut record))
+            | (Shape::Record(mut record), Shape::EmptyBraces { count }) => {
+                record.count += count;
+                Shape::Record(record)
+            }
+            (Shape::EmptyBraces { .. }, Shape::Flags(flags))
+            | (Shape::Flags(flags), Shape::EmptyBraces { .. }) => Shape::Flags(flags),
+            (Shape::Record(mut a), Shape::Record(b)) => {
+                a.count += b.count;
+                for (name, field) in b.fields {
+                    match a.fields.shift_remove_full(&name) {
+                        Some((idx, _, existing)) => {
+                            let shape =
+                                self.nested(&name, |cx| cx.merge(existing.shape, field.shape))?;
+                            let count = existing.count + field.count;
+                            a.fields.shift_insert(idx, name, Field { shape, count });
+                        }
+                        None => {
+                            a.fields.insert(name, field);
+                        }
+                    }
+                }
+                Shape::Record(a)
+            }
+            (Shape::Flags(mut a), Shape::Flags(b)) => {
+                a.extend(b);
+                Shape::Flags(a)
+            }
+            (Shape::Cases(mut a), Shape::Cases(b)) => {
+                for (name, payload) in b {
+                    let merged = match (a.shift_remove_full(&name), payload) {
+                        (None, payload) => payload,
+                        (Some((_, _, None)), None) => None,
+                        (Some((_, _, Some(a))), Some(b)) => {
+                            Some(self.nested(&name, |cx| cx.merge(a, b))?)
+                        }
+                        (Some((_, _, existing)), payload) => {
+                            let describe = |payload: &Option<Shape>| match payload {
+                                Some(_) => format!("`{name}` with a payload"),
+                                None => format!("`{name}` without a payload"),
+                            };
+                            return Err(self.conflict(describe(&existing), describe(&payload)));
+                        }
+                    };
+                    a.insert(name, merged);
+                }
+                Shape::Cases(a)
+            }
+            (cases @ Shape::Cases(_), other) | (other, cases @ Shape::Cases(_))
+                if cases.is_float_labels() && matches!(other, Shape::Int { .. } | Shape::Float) =>
+            {
+                Shape::Float
+            }
+            (Shape::Cases(mut cases), other) | (other, Shape::Cases(mut cases))
+                if flat_case(&cases).is_some() =>
+            {
+                // A flat option or result payload, e.g. `1` for `some(1)`
+                let name = flat_case(&cases).unwrap();
+                let merged = match cases.shift_remove(name).flatten() {
+                    Some(existing) => self.nested(name, |cx| cx.merge(existing, other))?,
+                    None => other,
+                };
+                cases.insert(name.into(), Some(merged));
+                Shape::Cases(cases)
+            }
+            (a, b) => return Err(self.conflict(a.describe(), b.describe())),
+        })
+    }
+
+    fn nested<T>(
+        &mut self,
+        name: &str,
+        f: impl FnOnce(&mut Self) -> Result<T, InferError>,
+    ) -> Result<T, InferError> {
+        let len = self.path.len();
+        self.path.push('.');
+        self.path.push_str(name);
+        let res = f(self);
+        self.path.truncate(len);
+        res
+    }
+
+    fn conflict(&self, expected: String, got: String) -> InferError {
+        InferError::Conflict {
+            index: self.index,
+            path: self.path.clone(),
+            expected,
+            got,
+        }
+    }
+}
+
+// Converts a shape into a type, recording anything undecided.
+fn resolve(shape: Shape, path: &mut String, undecided: &mut Vec<Undecided>) -> Option<Type> {
+    let mut note = |path: &str, reason: &str| {
+        undecided.push(Undecided {
+            path: path.to_string(),
+            reason: reason.to_string(),
+        })
+    };
+    match shape {
+        Shape::Unknown => {
+            note(path, "no values seen");
+            None
+        }
+        Shape::Int { min, max } => Some(int_type(min, max).unwrap_or(Type::FLOAT64)),
+        Shape::Float => Some(Type::FLOAT64),
+        Shape::Char => Some(Type::CHAR),
+        Shape::String => Some(Type::STRING),
+        Shape::List(element) => {
+            path.push_str("[]");
+            let element = resolve(*element, path, undecided);
+            path.truncate(path.len() - 2);
+            Some(Type::list(element?))
+        }
+        Shape::Tuple(elements) => {
+            let elements = elements
+                .into_iter()
+                .enumerate()
+                .map(|(idx, shape)| resolve_nested(shape, &idx.to_string(), path, undecided))
+                .collect::<Vec<_>>();
+            Type::tuple(elements.into_iter().collect::<Option<Vec<_>>>()?)
+        }
+        Shape::EmptyBraces { .. } => {
+            note(
+                path,
+                "only `{}` seen; could be flags or a record with only option fields",
+            );
+            None
+        }
+        Shape::Record(Record { fields, count }) => {
+            let fields = fields
+                .into_iter()
+                .map(|(name, field)| {
+                    let optional = field.count < count;
+                    let ty = resolve_nested(field.shape, &name, path, undecided);
+                    let ty = match ty {
+                        Some(ty) if optional && ty.kind() != WasmTypeKind::Option => {
+                            Some(Type::option(ty))
+                        }
+                        ty => ty,
+                    };
+                    (name, ty)
+                })
+                .collect::<Vec<_>>();
+            let fields = fields
+                .into_iter()
+                .map(|(name, ty)| Some((name, ty?)))
+                .collect::<Option<Vec<_>>>()?;
+            Type::record(fields)
+        }
+        Shape::Flags(flags) => Type::flags(flags),
+        Shape::Cases(cases) => resolve_cases(cases, path, undecided),
+    }
+}
+
+fn resolve_nested(
+    shape: Shape,
+    name: &str,
+    path: &mut String,
+    undecided: &mut Vec<Undecided>,
+) -> Option<Type> {
+    let len = path.len();
+    path.push('.');
+    path.push_str(name);
+    let ty = resolve(shape, path, undecided);
+    path.truncate(len);
+    ty
+}
+
+fn resolve_cases(
+    mut cases: IndexMap<Box<str>, Option<Shape>>,
+    path: &mut String,
+    undecided: &mut Vec<Undecided>,
+) -> Option<Type> {
+    let has_payload = cases.values().any(Option::is_some);
+    let only = |allowed: &[&str]| cases.keys().all(|name| allowed.contains(&name.as_ref()));
+    let mut note = |reason: String| {
+        undecided.push(Undecided {
+            path: path.clone(),
+            reason,
+        })
+    };
+
+    if !has_payload && only(&[parser::TRUE, parser::FALSE]) {
+        Some(Type::BOOL)
+    } else if !has_payload && only(&["inf", "nan"]) {
+        note("only `inf` or `nan` seen; could also be an enum".into());
+        Some(Type::FLOAT64)
+    } else if flat_case(&cases) == Some(parser::SOME) {
+        match cases.shift_remove(parser::SOME).flatten() {
+            Some(shape) => Some(Type::option(resolve_nested(
+                shape,
+                parser::SOME,
+                path,
+                undecided,
+            )?)),
+            None => {
+                note("only `none` seen; can't infer the option payload type".into());
+                None
+            }
+        }
+    } else if flat_case(&cases) == Some(parser::OK) {
+        let mut payload = |name: &str| match cases.shift_remove(name) {
+            Some(Some(shape)) => resolve_nested(shape, name, path, undecided).map(Some),
+            Some(None) => Some(None),
+            None => {
+                undecided.push(Undecided {
+                    path: path.clone(),
+                    reason: format!("`{name}` not seen; assuming no payload"),
+                });
+                Some(None)
+            }
+        };
+        let ok = payload(parser::OK)?;
+        let err = payload(parser::ERR)?;
+        Some(Type::result(ok, err))
+    } else if has_payload {
+        let cases = cases
+            .into_iter()
+            .map(|(name, shape)| {
+                let ty = match shape {
+                    Some(shape) => Some(resolve_nested(shape, &name, path, undecided)?),
+                    None => None,
+                };
+                Some((name, ty))
+            })
+            .collect::<Vec<_>>();
+        Type::variant(cases.into_iter().collect::<Option<Vec<_>>>()?)
+    } else {
+        note("only labels without payloads seen; could also be a variant".into());
+        Type::enum_ty(cases.into_keys())
+    }
+}
+
+// Returns the narrowest integer type containing `min..=max`.
+fn int_type(min: i128, max: i128) -> Option<Type> {
+    let fits = |lo: i128, hi: i128| lo <= min && max <= hi;
+    Some(if min >= 0 {
+        if fits(0, u8::MAX.into()) {
+            Type::U8
+        } else if fits(0, u16::MAX.into()) {
+            Type::U16
+        } else if fits(0, u32::MAX.into()) {
+            Type::U32
+        } else if fits(0, u64::MAX.into()) {
+            Type::U64
+        } else {
+            return None;
+        }
+    } else if fits(i8::MIN.into(), i8::MAX.into()) {
+        Type::S8
+    } else if fits(i16::MIN.into(), i16::MAX.into()) {
+        Type::S16
+    } else if fits(i32::MIN.into(), i32::MAX.into()) {
+        Type::S32
+    } else if fits(i64::MIN.into(), i64::MAX.into()) {
+        Type::S64
+    } else {
+        return None;
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infer_types() {
+        for (samples, expected) in [
+            (&["1", "255"][..], "u8"),
+            (&["1", "256"], "u16"),
+            (&["-1", "127"], "s8"),
+            (&["-1", "128"], "s16"),
+            (&["4294967296"], "u64"),
+            (&["0xff", "0b1"], "u8"),
+            (&["-2147483649"], "s64"),
+            (&["18446744073709551616"], "float64"),
+            (&["1", "1.5"], "float64"),
+            (&["1", "-inf", "nan"], "float64"),
+            (&["'x'"], "char"),
+            (&["\"x\""], "string"),
+            (&["true"], "bool"),
+            (&["[]", "[1, -1]"], "list<s8>"),
+            (&["(1, \"x\")", "(2, \"y\")"], "tuple<u8, string>"),
+            (&["{a: 1, b: 'x'}"], "record { a: u8, b: char }"),
+            (
+                &["{a: 1, b: 'x'}", "{a: 2}"],
+                "record { a: u8, b: option<char> }",
+            ),
+            (
+                &["{a: 1, b: none}", "{b: 'x', a: 2}"],
+                "record { a: u8, b: option<char> }",
+            ),
+            (&["{a: some(1)}", "{}"], "record { a: option<u8> }"),
+            (&["{read}", "{}", "{write, read}"], "flags { read, write }"),
+            (&["some(1)", "none"], "option<u8>"),
+            (&["2", "none"], "option<u8>"),
+            (
+                &["none", "some(none)", "some(some(1))"],
+                "option<option<u8>>",
+            ),
+            (&["[none, 1]"], "list<option<u8>>"),
+            (&["ok(1)", "err(\"no\")"], "result<u8, string>"),
+            (&["ok", "err"], "result"),
+            (&["1", "ok(2)", "err"], "result<u8>"),
+            (&["on(1)", "off"], "variant { on(u8), off }"),
+        ] {
+            let inferred = infer_type(samples.iter().copied())
+                .unwrap_or_else(|err| panic!("error inferring {samples:?}: {err}"));
+            assert_eq!(
+                inferred.ty().map(ToString::to_string).as_deref(),
+                Some(expected),
+                "for {samples:?}"
+            );
+            assert_eq!(inferred.undecided(), &[], "for {samples:?}");
+        }
+    }
+
+    #[test]
+    fn infer_undecided() {
+        for (samples, expected, undecided) in [
+            (
+                &["north", "south"][..],
+                Some("enum { north, south }"),
+                "value: only labels without payloads seen; could also be a variant",
+            ),
+            (&["[]"], None, "value[]: no values seen"),
+            (
+                &["{a: [none]}"],
+                None,
+                "value.a[]: only `none` seen; can't infer the option payload type",
+            ),
+            (
+                &["{}"],
+                None,
+                "value: only `{}` seen; could be flags or a record with only option fields",
+            ),
+            (
+                &["(1, err(2))"],
+                Some("tuple<u8, result<_, u8>>"),
+                "value.1: `ok` not seen; assuming no payload",
+            ),
+            (
+                &["inf"],
+                Some("float64"),
+                "value: only `inf` or `nan` seen; could also be an enum",
+            ),
+        ] {
+            let inferred = infer_type(samples.iter().copied()).unwrap();
+            assert_eq!(
+                inferred.ty().map(ToString::to_string).as_deref(),
+                expected,
+                "for {samples:?}"
+            );
+            let undecided = [undecided.to_string()];
+            assert_eq!(
+                inferred
+                    .undecided()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>(),
+                undecided,
+                "for {samples:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn infer_errors() {
+        for (samples, expected) in [
+            (&[][..], "no samples"),
+            (&["1", "[1"], "sample 1: "),
+            (
+                &["1", "'x'"],
+                "sample 1: at value: expected integer, got char",
+            ),
+            (
+                &["[1, 'x']"],
+                "sample 0: at value[]: expected integer, got char",
+            ),
+            (
+                &["(1, 2)", "(1,)"],
+                "sample 1: at value: expected tuple of 2 values, got tuple of 1 values",
+            ),
+            (
+                &["{a: 1}", "{a: \"x\"}"],
+                "sample 1: at value.a: expected integer, got string",
+            ),
+            (
+                &["{a: 1, a: 2}"],
+                "sample 0: at value: expected unique field names, got duplicate field `a`",
+            ),
+            (
+                &["{a: 1}", "{a}"],
+                "sample 1: at value: expected record, got flags",
+            ),
+            (
+                &["on(1)", "on"],
+                "sample 1: at value: expected `on` with a payload, got `on` without a payload",
+            ),
+            (
+                &["north", "1"],
+                "sample 1: at value: expected labels `north`, got integer",
+            ),
+        ] {
+            let err = infer_type(samples.iter().copied()).expect_err(&format!("{samples:?}"));
+            let msg = err.to_string();
+            assert!(msg.starts_with(expected), "for {samples:?}: {msg}");
+        }
+    }
+}
@@ -1,9 +1,13 @@
 //! Value enum for WAVE values.
 
 mod convert;
+mod infer;
 #[cfg(test)]
 mod tests;
 mod ty;
+mod type_expr;
+mod view;
+mod visit;
 
 mod func;
 #[cfg(feature = "wit")]
@@ -14,15 +18,19 @@ pub use wit::{resolve_wit_func_type, resolve_wit_type};
 
 use std::{borrow::Cow, collections::HashMap, sync::Arc};
 
-use self::ty::{
-    EnumType, FlagsType, ListType, OptionType, RecordType, ResultType, TupleType, TypeEnum,
-    VariantType,
-};
+use self::ty::TypeEnum;
 use crate::{canonicalize_nan32, canonicalize_nan64};
 use crate::{ty::maybe_unwrap, val::unwrap_val, WasmType, WasmValue};
 
 pub use func::FuncType;
-pub use ty::Type;
+pub use infer::{infer_type, InferError, InferredType, Undecided};
+pub use ty::{
+    EnumType, FlagsType, FutureType, HandleType, ListType, OptionType, RecordType, ResultType,
+    StreamType, TupleType, Type, TypeRef, VariantType,
+};
+pub use type_expr::TypeParseError;
+pub use view::ValueRef;
+pub use visit::{walk_type, TypeVisitor};
 
 /// A Value is a WAVE value, and implements [`WasmValue`].
 #[derive(Debug, Clone, PartialEq)]
@@ -51,39 +59,40 @@ pub(super) enum ValueEnum {
     Option(OptionValue),
     Result(ResultValue),
     Flags(Flags),
+    Handle(Handle),
 }
 
+/// A list value.
 #[derive(Debug, Clone, PartialEq)]
-#[doc(hidden)]
 pub struct List {
     ty: Arc<ListType>,
     elements: Vec<Value>,
 }
 
+/// A record value.
 #[derive(Debug, Clone, PartialEq)]
-#[doc(hidden)]
 pub struct Record {
     ty: Arc<RecordType>,
     fields: Vec<Value>,
 }
 
+/// A tuple value.
 #[derive(Debug, Clone, PartialEq)]
-#[doc(hidden)]
 pub struct Tuple {
     ty: Arc<TupleType>,
     elements: Vec<Value>,
 }
 
+/// A variant value.
 #[derive(Debug, Clone, PartialEq)]
-#[doc(hidden)]
 pub struct Variant {
     ty: Arc<VariantType>,
     case: usize,
     payload: Option<Box<Value>>,
 }
 
+/// An enum value.
 #[derive(Debug, Clone, PartialEq)]
-#[doc(hidden)]
 pub struct Enum {
     ty: Arc<EnumType>,
     case: usize,
@@ -103,13 +112,20 @@ pub struct ResultValue {
     value: Result<Option<Box<Value>>, Option<Box<Value>>>,
 }
 
+/// A flags value.
 #[derive(Debug, Clone, PartialEq)]
-#[doc(hidden)]
 pub struct Flags {
     ty: Arc<FlagsType>,
     flags: Vec<usize>,
 }
 
+/// An opaque handle value, e.g. an own resource handle or a stream.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Handle {
+    ty: TypeEnum,
+    id: u32,
+}
+
 macro_rules! impl_primitives {
     ($Self:ident, $(($case:ident, $ty:ty, $make:ident, $unwrap:ident)),*) => {
         $(
@@ -144,14 +160,15 @@ impl WasmValue for Value {
             ValueEnum::Float64(_) => Type::FLOAT64,
             ValueEnum::Char(_) => Type::CHAR,
             ValueEnum::String(_) => Type::STRING,
-            ValueEnum::List(inner) => Type(TypeEnum::List(inner.ty.clone())),
-            ValueEnum::Record(inner) => Type(TypeEnum::Record(inner.ty.clone())),
-            ValueEnum::Tuple(inner) => Type(TypeEnum::Tuple(inner.ty.clone())),
-            ValueEnum::Variant(inner) => Type(TypeEnum::Variant(inner.ty.clone())),
-            ValueEnum::Enum(inner) => Type(TypeEnum::Enum(inner.ty.clone())),
-            ValueEnum::Option(inner) => Type(TypeEnum::Option(inner.ty.clone())),
-            ValueEnum::Result(inner) => Type(TypeEnum::Result(inner.ty.clone())),
-            ValueEnum::Flags(inner) => Type(TypeEnum::Flags(inner.ty.clone())),
+            ValueEnum::List(inner) => Type(TypeEnum::List(inner.ty.clone()), None),
+            ValueEnum::Record(inner) => Type(TypeEnum::Record(inner.ty.clone()), None),
+            ValueEnum::Tuple(inner) => Type(TypeEnum::Tuple(inner.ty.clone()), None),
+            ValueEnum::Variant(inner) => Type(TypeEnum::Variant(inner.ty.clone()), None),
+            ValueEnum::Enum(inner) => Type(TypeEnum::Enum(inner.ty.clone()), None),
+            ValueEnum::Option(inner) => Type(TypeEnum::Option(inner.ty.clone()), None),
+            ValueEnum::Result(inner) => Type(TypeEnum::Result(inner.ty.clone()), None),
+            ValueEnum::Flags(inner) => Type(TypeEnum::Flags(inner.ty.clone()), None),
+            ValueEnum::Handle(inner) => Type(inner.ty.clone(), None),
         }
     }
 
@@ -190,10 +207,18 @@ impl WasmValue for Value {
         let element_type = ty
             .list_element_type()
             .ok_or_else(|| ValueError::InvalidType(format!("{ty:?} is not a valid list type")))?;
-        let elements = vals
+        let elements: Vec<_> = vals
             .into_iter()
             .map(|v| check_type(&element_type, v))
             .collect::<Result<_, ValueError>>()?;
+        if let Some(length) = ty.list_length() {
+            if elements.len() != length as usize {
+                return Err(ValueError::InvalidValue(format!(
+                    "expected {length} list elements, got {}",
+                    elements.len()
+                )));
+            }
+        }
         let ty = maybe_unwrap!(&ty.0, TypeEnum::List).unwrap().clone();
         Ok(Self(ValueEnum::List(List { ty, elements })))
     }
@@ -315,6 +340,16 @@ impl WasmValue for Value {
         Ok(Self(ValueEnum::Flags(Flags { ty, flags })))
     }
 
+    fn make_handle(ty: &Self::Type, id: u32) -> Result<Self, Self::Error> {
+        if !crate::ty::is_handle(ty.kind()) {
+            return Err(ValueError::InvalidType(format!(
+                "{ty:?} is not a handle type"
+            )));
+        }
+        let ty = ty.0.clone();
+        Ok(Self(ValueEnum::Handle(Handle { ty, id })))
+    }
+
     fn unwrap_float32(&self) -> f32 {
         let val = *unwrap_val!(&self.0, ValueEnum::Float32, "float32");
         canonicalize_nan32(val)
@@ -325,16 +360,16 @@ impl WasmValue for Value {
         canonicalize_nan64(val)
     }
 
-    fn unwrap_string(&self) -> std::borrow::Cow<str> {
+    fn unwrap_string(&self) -> std::borrow::Cow<'_, str> {
         unwrap_val!(&self.0, ValueEnum::String, "string")
             .as_ref()
             .into()
     }
-    fn unwrap_list(&self) -> Box<dyn Iterator<Item = Cow<Self>> + '_> {
+    fn unwrap_list(&self) -> Box<dyn Iterator<Item = Cow<'_, Self>> + '_> {
         let list = unwrap_val!(&self.0, ValueEnum::List, "list");
         Box::new(list.elements.iter().map(cow))
     }
-    fn unwrap_record(&self) -> Box<dyn Iterator<Item = (Cow<str>, Cow<Self>)> + '_> {
+    fn unwrap_record(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, Cow<'_, Self>)> + '_> {
         let record = unwrap_val!(&self.0, ValueEnum::Record, "record");
         Box::new(
             record
@@ -345,32 +380,32 @@ impl WasmValue for Value {
                 .zip(record.fields.iter().map(cow)),
         )
     }
-    fn unwrap_tuple(&self) -> Box<dyn Iterator<Item = Cow<Self>> + '_> {
+    fn unwrap_tuple(&self) -> Box<dyn Iterator<Item = Cow<'_, Self>> + '_> {
         let tuple = unwrap_val!(&self.0, ValueEnum::Tuple, "tuple");
         Box::new(tuple.elements.iter().map(cow))
     }
-    fn unwrap_variant(&self) -> (Cow<str>, Option<Cow<Self>>) {
+    fn unwrap_variant(&self) -> (Cow<'_, str>, Option<Cow<'_, Self>>) {
         let variant = unwrap_val!(&self.0, ValueEnum::Variant, "variant");
         let (ref name, _) = variant.ty.cases[variant.case];
         (cow(name.as_ref()), variant.payload.as_deref().map(cow))
     }
-    fn unwrap_enum(&self) -> Cow<str> {
+    fn unwrap_enum(&self) -> Cow<'_, str> {
         let enum_ = unwrap_val!(&self.0, ValueEnum::Enum, "enum");
         cow(enum_.ty.cases[enum_.case].as_ref())
     }
-    fn unwrap_option(&self) -> Option<Cow<Self>> {
+    fn unwrap_option(&self) -> Option<Cow<'_, Self>> {
         unwrap_val!(&self.0, ValueEnum::Option, "option")
             .value
             .as_ref()
             .map(|v| cow(v.as_ref()))
     }
-    fn unwrap_result(&self) -> Result<Option<Cow<Self>>, Option<Cow<Self>>> {
+    fn unwrap_result(&self) -> Result<Option<Cow<'_, Self>>, Option<Cow<'_, Self>>> {
         match &unwrap_val!(&self.0, ValueEnum::Result, "result").value {
             Ok(val) => Ok(val.as_deref().map(cow)),
             Err(val) => Err(val.as_deref().map(cow)),
         }
     }
-    fn unwrap_flags(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn unwrap_flags(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         let flags = unwrap_val!(&self.0, ValueEnum::Flags, "flags");
         Box::new(
             flags
@@ -379,9 +414,12 @@ impl WasmValue for Value {
                 .map(|idx| cow(flags.ty.flags[*idx].as_ref())),
         )
     }
+    fn unwrap_handle(&self) -> u32 {
+        unwrap_val!(&self.0, ValueEnum::Handle, "handle").id
+    }
 }
 
-fn cow<T: ToOwned + ?Sized>(t: &T) -> Cow<T> {
+fn cow<T: ToOwned + ?Sized>(t: &T) -> Cow<'_, T> {
     Cow::Borrowed(t)
 }
 
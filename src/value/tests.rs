@@ -45,6 +45,16 @@ fn list_round_trips() {
     test_value_round_trip(Value::make_list(&ty, [Value::make_u8(1), Value::make_u8(2)]).unwrap());
 }
 
+#[test]
+fn fixed_list_round_trips() {
+    let ty = Type::fixed_list(Type::STRING, 2).unwrap();
+    test_value_round_trip(Value::make_list(&ty, ["a", "b"].map(Value::from)).unwrap());
+    Value::make_list(&ty, [Value::from("a")]).unwrap_err();
+    // A fixed-length list type is distinct from its variable-length list type
+    let val = Value::make_list(&Type::list(Type::STRING), ["a", "b"].map(Value::from)).unwrap();
+    assert_ne!(val.ty(), ty);
+}
+
 #[test]
 fn record_round_trip() {
     let option_ty = Type::option(Type::U8);
@@ -118,9 +128,396 @@ fn flags_round_trips() {
     test_value_round_trip(Value::make_flags(&ty, ["read", "execute"]).unwrap());
 }
 
+#[test]
+fn handle_round_trips() {
+    for ty in [Type::own("file"), Type::borrow("input-stream")] {
+        test_value_round_trip(Value::make_handle(&ty, 0).unwrap());
+        test_value_round_trip(Value::make_handle(&ty, u32::MAX).unwrap());
+    }
+    let ty = Type::own("file");
+    let val: Value = crate::from_str(&ty, "file(3)").unwrap();
+    assert_eq!(val.unwrap_handle(), 3);
+    for input in ["3", "file", "file(-1)", "dir(3)"] {
+        crate::from_str::<Value>(&ty, input).unwrap_err();
+    }
+}
+
+#[test]
+fn async_handle_round_trips() {
+    for (ty, expected) in [
+        (Type::stream(Some(Type::U8)), "stream(1)"),
+        (Type::stream(None), "stream(1)"),
+        (Type::future(Some(Type::STRING)), "future(1)"),
+        (Type::ERROR_CONTEXT, "error-context(1)"),
+    ] {
+        let val = Value::make_handle(&ty, 1).unwrap();
+        assert_eq!(crate::to_string(&val).unwrap(), expected);
+        test_value_round_trip(val);
+    }
+    // Handles of different types are not interchangeable
+    crate::from_str::<Value>(&Type::future(None), "stream(1)").unwrap_err();
+    Value::make_handle(&Type::U32, 1).unwrap_err();
+}
+
+#[test]
+fn hex_unsigned_round_trips() {
+    for (val, expected) in [
+        (Value::make_u8(0), "0x0"),
+        (Value::make_u16(0xabc), "0xabc"),
+        (Value::make_u32(u32::MAX), "0xffffffff"),
+        (Value::make_u64(u64::MAX), "0xffffffffffffffff"),
+        (Value::make_s8(-1), "-1"),
+    ] {
+        let mut buf = vec![];
+        crate::writer::Writer::new(&mut buf)
+            .hex_unsigned(true)
+            .write_value(&val)
+            .unwrap();
+        let serialized = String::from_utf8(buf).unwrap();
+        assert_eq!(serialized, expected);
+        let deserialized: Value = crate::from_str(&val.ty(), &serialized).unwrap();
+        assert_eq!(deserialized, val);
+    }
+}
+
 fn test_value_round_trip(val: Value) {
     let ty = val.ty();
     let serialized = crate::to_string(&val).unwrap();
     let deserialized: Value = crate::from_str(&ty, &serialized).unwrap();
     assert_eq!(deserialized, val, "for {val:?}");
 }
+
+#[test]
+fn pretty_round_trips() {
+    let ty = Type::list(
+        Type::record([
+            ("name", Type::STRING),
+            ("tags", Type::list(Type::STRING)),
+            ("perms", Type::flags(["read", "write", "exec"]).unwrap()),
+            (
+                "pos",
+                Type::option(Type::tuple([Type::S32, Type::S32]).unwrap()),
+            ),
+        ])
+        .unwrap(),
+    );
+    let input = r#"[
+        {name: "a", tags: [], perms: {read}, pos: some((1, 2))},
+        {name: "bbbbbbbbbb", tags: ["one", "two", "three", "four"], perms: {read, write, exec}},
+    ]"#;
+    let val: Value = crate::from_str(&ty, input).unwrap();
+
+    for (line_width, expected) in [
+        (
+            200,
+            r#"[{name: "a", tags: [], perms: {read}, pos: some((1, 2))}, {name: "bbbbbbbbbb", tags: ["one", "two", "three", "four"], perms: {read, write, exec}}]"#,
+        ),
+        (
+            80,
+            r#"[
+  {name: "a", tags: [], perms: {read}, pos: some((1, 2))},
+  {
+    name: "bbbbbbbbbb",
+    tags: ["one", "two", "three", "four"],
+    perms: {read, write, exec},
+  },
+]"#,
+        ),
+        (
+            20,
+            r#"[
+  {
+    name: "a",
+    tags: [],
+    perms: {read},
+    pos: some((1, 2)),
+  },
+  {
+    name: "bbbbbbbbbb",
+    tags: [
+      "one",
+      "two",
+      "three",
+      "four",
+    ],
+    perms: {
+      read,
+      write,
+      exec,
+    },
+  },
+]"#,
+        ),
+    ] {
+        let mut buf = vec![];
+        crate::writer::Writer::new(&mut buf)
+            .pretty(true)
+            .line_width(line_width)
+            .write_value(&val)
+            .unwrap();
+        let serialized = String::from_utf8(buf).unwrap();
+        assert_eq!(serialized, expected, "for line width {line_width}");
+        let deserialized: Value = crate::from_str(&ty, &serialized).unwrap();
+        assert_eq!(deserialized, val);
+    }
+
+    // Indentation is configurable
+    let mut buf = vec![];
+    crate::writer::Writer::new(&mut buf)
+        .pretty(true)
+        .indent(4)
+        .line_width(0)
+        .write_value(&Value::from([1u8]))
+        .unwrap();
+    assert_eq!(buf, b"[\n    1,\n]");
+}
+
+#[test]
+fn flat_payloads_round_trip() {
+    let option_ty = Type::option;
+    let result_ty = |ok| Type::result(Some(ok), Some(Type::STRING));
+    for (ty, input, expected) in [
+        (option_ty(Type::U8), "some(1)", "1"),
+        (option_ty(Type::U8), "none", "none"),
+        (option_ty(Type::BOOL), "some(false)", "false"),
+        (option_ty(Type::STRING), r#"some("none")"#, r#""none""#),
+        (
+            option_ty(Type::list(option_ty(Type::U8))),
+            "some([some(1), none])",
+            "[1, none]",
+        ),
+        (option_ty(option_ty(Type::U8)), "some(some(1))", "some(1)"),
+        (
+            option_ty(Type::enum_ty(["none", "some"]).unwrap()),
+            "some(%none)",
+            "some(%none)",
+        ),
+        (result_ty(Type::FLOAT32), "ok(nan)", "nan"),
+        (result_ty(Type::STRING), r#"ok("err")"#, r#""err""#),
+        (result_ty(Type::STRING), r#"err("oops")"#, r#"err("oops")"#),
+        (result_ty(option_ty(Type::U8)), "ok(none)", "ok(none)"),
+        (Type::result(None, None), "ok", "ok"),
+        // Handles are written as a label and payload, like variant cases
+        (
+            option_ty(Type::own("none")),
+            "some(%none(3))",
+            "some(%none(3))",
+        ),
+        (
+            option_ty(Type::own("some")),
+            "some(%some(3))",
+            "some(%some(3))",
+        ),
+        (result_ty(Type::own("ok")), "ok(%ok(3))", "ok(%ok(3))"),
+        (result_ty(Type::borrow("err")), "ok(%err(3))", "ok(%err(3))"),
+        (
+            option_ty(Type::stream(Some(Type::U8))),
+            "some(stream(3))",
+            "some(stream(3))",
+        ),
+    ] {
+        let val: Value = crate::from_str(&ty, input).unwrap();
+        let mut buf = vec![];
+        crate::writer::Writer::new(&mut buf)
+            .flat_payloads(true)
+            .write_value(&val)
+            .unwrap();
+        let serialized = String::from_utf8(buf).unwrap();
+        assert_eq!(serialized, expected, "for {input}");
+        let deserialized: Value = crate::from_str(&ty, &serialized).unwrap();
+        assert_eq!(
+            crate::to_string(&deserialized).unwrap(),
+            crate::to_string(&val).unwrap()
+        );
+    }
+}
+
+#[test]
+fn keyword_labels_round_trip() {
+    let ty = Type::record([
+        (
+            "true",
+            Type::enum_ty(["none", "ok", "nan", "some-thing"]).unwrap(),
+        ),
+        ("inf", Type::flags(["false", "err", "X-Y2"]).unwrap()),
+        (
+            "none",
+            Type::variant([("some", Some(Type::U8)), ("err", None)]).unwrap(),
+        ),
+    ])
+    .unwrap();
+    let input = "{%true: %none, %inf: {%false, %err, X-Y2}, %none: %some(1)}";
+    let val: Value = crate::from_str(&ty, input).unwrap();
+    let serialized = crate::to_string(&val).unwrap();
+    assert_eq!(serialized, input);
+    let deserialized: Value = crate::from_str(&ty, &serialized).unwrap();
+    assert_eq!(deserialized, val);
+}
+
+#[test]
+fn invalid_labels_fail_to_write() {
+    for label in ["", "a--b", "-a", "a-", "1a", "aB", "a_b", "%a", "a b", "é"] {
+        let ty = Type::enum_ty([label]).unwrap();
+        let val = Value::make_enum(&ty, label).unwrap();
+        let err = crate::to_string(&val).unwrap_err();
+        assert!(
+            matches!(&err, crate::writer::WriterError::InvalidLabel(got) if got == label),
+            "{label:?}: {err:?}"
+        );
+    }
+}
+
+#[test]
+fn escapes_round_trip() {
+    use crate::writer::{Escape, Writer};
+
+    assert_eq!(Escape::default(), Escape::Printable);
+    let val = Value::from("a\\\"'\t\n\0\u{1b}\u{7f}\u{a0}é\u{202e}\u{2067}\u{301}☃😀");
+    for (escape, expected) in [
+        (
+            Escape::Printable,
+            r#""a\\\"\'\t\n\u{0}\u{1b}\u{7f}\u{a0}é\u{202e}\u{2067}\u{301}☃😀""#,
+        ),
+        (
+            Escape::Ascii,
+            r#""a\\\"\'\t\n\u{0}\u{1b}\u{7f}\u{a0}\u{e9}\u{202e}\u{2067}\u{301}\u{2603}\u{1f600}""#,
+        ),
+    ] {
+        let mut buf = vec![];
+        Writer::new(&mut buf)
+            .escape(escape)
+            .write_value(&val)
+            .unwrap();
+        let serialized = String::from_utf8(buf).unwrap();
+        assert_eq!(serialized, expected, "for {escape:?}");
+        let deserialized: Value = crate::from_str(&Type::STRING, &serialized).unwrap();
+        assert_eq!(deserialized, val, "for {escape:?}");
+
+        for ch in val.unwrap_string().chars() {
+            let mut buf = vec![];
+            Writer::new(&mut buf)
+                .escape(escape)
+                .write_value(&Value::make_char(ch))
+                .unwrap();
+            let serialized = String::from_utf8(buf).unwrap();
+            let deserialized: Value = crate::from_str(&Type::CHAR, &serialized).unwrap();
+            assert_eq!(deserialized.unwrap_char(), ch, "for {escape:?}");
+        }
+    }
+}
+
+#[test]
+fn printable_escape_is_unchanged() {
+    // The escaping Writer has always done, and still does by default
+    fn escape(ch: char) -> String {
+        if "\\\"\'\t\r\n".contains(ch) {
+            ch.escape_default().to_string()
+        } else if ch.is_control() {
+            ch.escape_unicode().to_string()
+        } else {
+            ch.escape_debug().to_string()
+        }
+    }
+
+    let s = ('\0'..='\u{3000}').chain(['\u{feff}', '\u{e0001}', '\u{10ffff}']);
+    let expected = format!("\"{}\"", s.clone().map(escape).collect::<String>());
+    let val = Value::from(s.collect::<String>());
+    assert_eq!(crate::to_string(&val).unwrap(), expected);
+}
+
+#[test]
+fn fmt_write_matches_io_write() {
+    use crate::writer::{FmtWrite, Writer};
+
+    let ty = Type::list(Type::tuple([Type::STRING, Type::CHAR]).unwrap());
+    let input = r#"[("a☃\n", '😀'), ("", '\'')]"#;
+    let val: Value = crate::from_str(&ty, input).unwrap();
+
+    let mut buf = vec![];
+    Writer::new(&mut buf)
+        .pretty(true)
+        .line_width(10)
+        .write_value(&val)
+        .unwrap();
+    let mut s = String::new();
+    Writer::new(FmtWrite(&mut s))
+        .pretty(true)
+        .line_width(10)
+        .write_value(&val)
+        .unwrap();
+    assert_eq!(s.as_bytes(), buf);
+
+    assert_eq!(crate::to_string(&val).unwrap(), input);
+    assert_eq!(crate::fmt::DisplayValue(&val).to_string(), input);
+}
+
+#[test]
+fn func_args_round_trip() {
+    use crate::{parser::Parser, value::FuncType, writer::Writer};
+
+    let opt = Type::option(Type::U8);
+    let rec = Type::record([("a", Type::U8), ("b", opt.clone())]).unwrap();
+    let func = FuncType::new(
+        [
+            ("x".into(), opt.clone()),
+            ("y".into(), rec.clone()),
+            ("z".into(), opt.clone()),
+            ("w".into(), opt.clone()),
+        ],
+        [],
+    )
+    .unwrap();
+    for (input, omitted, all) in [
+        (
+            "(none, {a: 1}, none, none)",
+            "(none, {a: 1})",
+            "(none, {a: 1, b: none}, none, none)",
+        ),
+        (
+            "(some(1), {a: 1, b: some(2)}, none, some(3))",
+            "(some(1), {a: 1, b: some(2)}, none, some(3))",
+            "(some(1), {a: 1, b: some(2)}, none, some(3))",
+        ),
+        (
+            "(none, {a: 1}, some(2))",
+            "(none, {a: 1}, some(2))",
+            "(none, {a: 1, b: none}, some(2), none)",
+        ),
+    ] {
+        let args: Vec<Value> = Parser::new(input).parse_func_args(&func).unwrap();
+        for (omit_none, expected) in [(true, omitted), (false, all)] {
+            let mut buf = vec![];
+            Writer::new(&mut buf)
+                .omit_none(omit_none)
+                .write_func_args(&func, &args)
+                .unwrap();
+            let serialized = String::from_utf8(buf).unwrap();
+            assert_eq!(serialized, expected, "for {input}");
+            let deserialized: Vec<Value> = Parser::new(&serialized).parse_func_args(&func).unwrap();
+            assert_eq!(deserialized, args);
+        }
+    }
+
+    // No params
+    let func = FuncType::new([], []).unwrap();
+    let mut buf = vec![];
+    Writer::new(&mut buf)
+        .write_func_args(&func, &[] as &[Value])
+        .unwrap();
+    assert_eq!(buf, b"()");
+
+    // Wrong number of args
+    let err = Writer::new(vec![])
+        .write_func_args(&func, &[Value::from(1u8)])
+        .unwrap_err();
+    assert!(
+        matches!(
+            err,
+            crate::writer::WriterError::ArgCount {
+                expected: 0,
+                got: 1
+            }
+        ),
+        "{err:?}"
+    );
+}
@@ -6,8 +6,19 @@ use crate::{
 };
 
 /// The [`WasmType`] of a [`Value`](super::Value).
-#[derive(Clone, Debug, PartialEq)]
-pub struct Type(pub(super) TypeEnum);
+///
+/// A type may have a name, e.g. from a WIT type definition. Names are
+/// ignored when comparing types, and values don't keep the names of their
+/// types.
+#[derive(Clone, Debug)]
+pub struct Type(pub(super) TypeEnum, pub(super) Option<Arc<TypeName>>);
+
+// The name of a named type, and the WIT interface it was defined in, if any.
+#[derive(Debug)]
+pub(super) struct TypeName {
+    name: Box<str>,
+    interface: Option<Box<str>>,
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub(super) enum TypeEnum {
@@ -20,6 +31,9 @@ pub(super) enum TypeEnum {
     Option(Arc<OptionType>),
     Result(Arc<ResultType>),
     Flags(Arc<FlagsType>),
+    Handle(Arc<HandleType>),
+    Stream(Arc<StreamType>),
+    Future(Arc<FutureType>),
 }
 
 #[allow(missing_docs)]
@@ -37,11 +51,12 @@ impl Type {
     pub const FLOAT64: Self = Self::must_simple(WasmTypeKind::Float64);
     pub const CHAR: Self = Self::must_simple(WasmTypeKind::Char);
     pub const STRING: Self = Self::must_simple(WasmTypeKind::String);
+    pub const ERROR_CONTEXT: Self = Self::must_simple(WasmTypeKind::ErrorContext);
 
     /// Returns the simple type of the given `kind`. Returns None if the kind
     /// represents a parameterized type.
     pub fn simple(kind: WasmTypeKind) -> Option<Self> {
-        is_simple(kind).then_some(Self(TypeEnum::Simple(SimpleType(kind))))
+        is_simple(kind).then_some(Self(TypeEnum::Simple(SimpleType(kind)), None))
     }
 
     #[doc(hidden)]
@@ -49,13 +64,35 @@ impl Type {
         if !is_simple(kind) {
             panic!("kind is not simple");
         }
-        Self(TypeEnum::Simple(SimpleType(kind)))
+        Self(TypeEnum::Simple(SimpleType(kind)), None)
     }
 
     /// Returns a list type with the given element type.
     pub fn list(element_type: impl Into<Self>) -> Self {
         let element = element_type.into();
-        Self(TypeEnum::List(Arc::new(ListType { element })))
+        Self(
+            TypeEnum::List(Arc::new(ListType {
+                element,
+                length: None,
+            })),
+            None,
+        )
+    }
+
+    /// Returns a fixed-length list type with the given element type and
+    /// length. Returns None if `length` is zero.
+    pub fn fixed_list(element_type: impl Into<Self>, length: u32) -> Option<Self> {
+        if length == 0 {
+            return None;
+        }
+        let element = element_type.into();
+        Some(Self(
+            TypeEnum::List(Arc::new(ListType {
+                element,
+                length: Some(length),
+            })),
+            None,
+        ))
     }
 
     /// Returns a record type with the given field types. Returns None if
@@ -70,7 +107,10 @@ impl Type {
         if fields.is_empty() {
             return None;
         }
-        Some(Self(TypeEnum::Record(Arc::new(RecordType { fields }))))
+        Some(Self(
+            TypeEnum::Record(Arc::new(RecordType { fields })),
+            None,
+        ))
     }
 
     /// Returns a tuple type with the given element types. Returns None if
@@ -80,7 +120,10 @@ impl Type {
         if elements.is_empty() {
             return None;
         }
-        Some(Self(TypeEnum::Tuple(Arc::new(TupleType { elements }))))
+        Some(Self(
+            TypeEnum::Tuple(Arc::new(TupleType { elements })),
+            None,
+        ))
     }
 
     /// Returns a variant type with the given case names and optional payloads.
@@ -95,7 +138,10 @@ impl Type {
         if cases.is_empty() {
             return None;
         }
-        Some(Self(TypeEnum::Variant(Arc::new(VariantType { cases }))))
+        Some(Self(
+            TypeEnum::Variant(Arc::new(VariantType { cases })),
+            None,
+        ))
     }
 
     /// Returns an enum type with the given case names. Returns None if `cases`
@@ -105,17 +151,17 @@ impl Type {
         if cases.is_empty() {
             return None;
         }
-        Some(Self(TypeEnum::Enum(Arc::new(EnumType { cases }))))
+        Some(Self(TypeEnum::Enum(Arc::new(EnumType { cases })), None))
     }
 
     /// Returns an option type with the given "some" type.
     pub fn option(some: Self) -> Self {
-        Self(TypeEnum::Option(Arc::new(OptionType { some })))
+        Self(TypeEnum::Option(Arc::new(OptionType { some })), None)
     }
 
     /// Returns a result type with the given optional "ok" and "err" payloads.
     pub fn result(ok: Option<Self>, err: Option<Self>) -> Self {
-        Self(TypeEnum::Result(Arc::new(ResultType { ok, err })))
+        Self(TypeEnum::Result(Arc::new(ResultType { ok, err })), None)
     }
 
     /// Returns a flags type with the given flag names. Returns None if `flags`
@@ -125,7 +171,87 @@ impl Type {
         if flags.is_empty() {
             return None;
         }
-        Some(Self(TypeEnum::Flags(Arc::new(FlagsType { flags }))))
+        Some(Self(TypeEnum::Flags(Arc::new(FlagsType { flags })), None))
+    }
+
+    /// Returns this type with the given name and optionally the name of the
+    /// interface it was defined in, e.g. `example:app/types`.
+    /// ```
+    /// # use wasm_wave::value::Type;
+    /// let ty = Type::list(Type::U8).with_name("bytes", Some("example:app/types"));
+    /// assert_eq!(ty.name(), Some("bytes"));
+    /// assert_eq!(ty.interface(), Some("example:app/types"));
+    /// assert_eq!(format!("{ty:#}"), "bytes");
+    /// assert_eq!(ty, Type::list(Type::U8));
+    /// ```
+    pub fn with_name(mut self, name: impl Into<Box<str>>, interface: Option<&str>) -> Self {
+        self.1 = Some(Arc::new(TypeName {
+            name: name.into(),
+            interface: interface.map(Into::into),
+        }));
+        self
+    }
+
+    /// Returns the name of this type, if it is named.
+    pub fn name(&self) -> Option<&str> {
+        Some(&self.1.as_ref()?.name)
+    }
+
+    /// Returns the name of the interface this type was defined in, if known.
+    pub fn interface(&self) -> Option<&str> {
+        self.1.as_ref()?.interface.as_deref()
+    }
+
+    /// Returns an own handle type for the resource with the given name.
+    pub fn own(resource: impl Into<Box<str>>) -> Self {
+        Self::handle(resource.into(), false)
+    }
+
+    /// Returns a borrow handle type for the resource with the given name.
+    pub fn borrow(resource: impl Into<Box<str>>) -> Self {
+        Self::handle(resource.into(), true)
+    }
+
+    fn handle(resource: Box<str>, borrow: bool) -> Self {
+        Self(
+            TypeEnum::Handle(Arc::new(HandleType { resource, borrow })),
+            None,
+        )
+    }
+
+    /// Returns a stream type with the given optional element type.
+    pub fn stream(element: Option<Self>) -> Self {
+        Self(TypeEnum::Stream(Arc::new(StreamType { element })), None)
+    }
+
+    /// Returns a future type with the given optional payload type.
+    pub fn future(payload: Option<Self>) -> Self {
+        Self(TypeEnum::Future(Arc::new(FutureType { payload })), None)
+    }
+
+    /// Returns a borrowed view of the structure of this type.
+    /// ```
+    /// # use wasm_wave::value::{Type, TypeRef};
+    /// let ty = Type::record([("id", Type::U32), ("tags", Type::list(Type::STRING))]).unwrap();
+    /// let TypeRef::Record(record) = ty.view() else { unreachable!() };
+    /// let names = record.fields().iter().map(|(name, _)| &**name).collect::<Vec<_>>();
+    /// assert_eq!(names, ["id", "tags"]);
+    /// ```
+    pub fn view(&self) -> TypeRef<'_> {
+        match &self.0 {
+            TypeEnum::Simple(simple) => TypeRef::Simple(simple.0),
+            TypeEnum::List(list) => TypeRef::List(list),
+            TypeEnum::Record(record) => TypeRef::Record(record),
+            TypeEnum::Tuple(tuple) => TypeRef::Tuple(tuple),
+            TypeEnum::Variant(variant) => TypeRef::Variant(variant),
+            TypeEnum::Enum(enum_) => TypeRef::Enum(enum_),
+            TypeEnum::Option(option) => TypeRef::Option(option),
+            TypeEnum::Result(result) => TypeRef::Result(result),
+            TypeEnum::Flags(flags) => TypeRef::Flags(flags),
+            TypeEnum::Handle(handle) => TypeRef::Handle(handle),
+            TypeEnum::Stream(stream) => TypeRef::Stream(stream),
+            TypeEnum::Future(future) => TypeRef::Future(future),
+        }
     }
 
     /// Returns a [`Type`] matching the given [`WasmType`]. Returns None if the
@@ -135,6 +261,28 @@ impl Type {
     }
 }
 
+/// A borrowed view of the structure of a [`Type`], returned by
+/// [`Type::view`].
+#[derive(Clone, Copy, Debug)]
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub enum TypeRef<'a> {
+    /// A type without any child types, e.g. `u8` or `string`.
+    Simple(WasmTypeKind),
+    List(&'a ListType),
+    Record(&'a RecordType),
+    Tuple(&'a TupleType),
+    Variant(&'a VariantType),
+    Enum(&'a EnumType),
+    Option(&'a OptionType),
+    Result(&'a ResultType),
+    Flags(&'a FlagsType),
+    /// An own or borrow resource handle type.
+    Handle(&'a HandleType),
+    Stream(&'a StreamType),
+    Future(&'a FutureType),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct SimpleType(WasmTypeKind);
 
@@ -142,52 +290,195 @@ const fn is_simple(kind: WasmTypeKind) -> bool {
     use WasmTypeKind::*;
     matches!(
         kind,
-        Bool | S8 | S16 | S32 | S64 | U8 | U16 | U32 | U64 | Float32 | Float64 | Char | String
+        Bool | S8
+            | S16
+            | S32
+            | S64
+            | U8
+            | U16
+            | U32
+            | U64
+            | Float32
+            | Float64
+            | Char
+            | String
+            | ErrorContext
     )
 }
 
+/// A list type, possibly of fixed length.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ListType {
     pub(super) element: Type,
+    pub(super) length: Option<u32>,
 }
 
+/// A record type.
 #[derive(Debug, PartialEq)]
 pub struct RecordType {
     pub(super) fields: Box<[(Box<str>, Type)]>,
 }
 
+/// A tuple type.
 #[derive(Debug, PartialEq)]
 pub struct TupleType {
     pub(super) elements: Box<[Type]>,
 }
 
+/// A variant type.
 #[derive(Debug, PartialEq)]
 pub struct VariantType {
     pub(super) cases: Box<[(Box<str>, Option<Type>)]>,
 }
 
+/// An enum type.
 #[derive(Debug, PartialEq)]
 pub struct EnumType {
     pub(super) cases: Box<[Box<str>]>,
 }
 
+/// An option type.
 #[derive(Debug, PartialEq)]
 pub struct OptionType {
     pub(super) some: Type,
 }
 
+/// A result type.
 #[derive(Debug, PartialEq)]
 pub struct ResultType {
     pub(super) ok: Option<Type>,
     pub(super) err: Option<Type>,
 }
 
+/// A flags type.
 #[derive(Debug, PartialEq)]
 pub struct FlagsType {
     pub(super) flags: Box<[Box<str>]>,
 }
 
+/// An own or borrow resource handle type.
+#[derive(Debug, PartialEq)]
+pub struct HandleType {
+    pub(super) resource: Box<str>,
+    pub(super) borrow: bool,
+}
+
+/// A stream type.
+#[derive(Debug, PartialEq)]
+pub struct StreamType {
+    pub(super) element: Option<Type>,
+}
+
+/// A future type.
+#[derive(Debug, PartialEq)]
+pub struct FutureType {
+    pub(super) payload: Option<Type>,
+}
+
+impl ListType {
+    /// Returns the element type.
+    pub fn element(&self) -> &Type {
+        &self.element
+    }
+
+    /// Returns the length of a fixed-length list type.
+    pub fn length(&self) -> Option<u32> {
+        self.length
+    }
+}
+
+impl RecordType {
+    /// Returns the field names and types.
+    pub fn fields(&self) -> &[(Box<str>, Type)] {
+        &self.fields
+    }
+}
+
+impl TupleType {
+    /// Returns the element types.
+    pub fn elements(&self) -> &[Type] {
+        &self.elements
+    }
+}
+
+impl VariantType {
+    /// Returns the case names and optional payload types.
+    pub fn cases(&self) -> &[(Box<str>, Option<Type>)] {
+        &self.cases
+    }
+}
+
+impl EnumType {
+    /// Returns the case names.
+    pub fn cases(&self) -> &[Box<str>] {
+        &self.cases
+    }
+}
+
+impl OptionType {
+    /// Returns the "some" type.
+    pub fn some(&self) -> &Type {
+        &self.some
+    }
+}
+
+impl ResultType {
+    /// Returns the optional "ok" type.
+    pub fn ok(&self) -> Option<&Type> {
+        self.ok.as_ref()
+    }
+
+    /// Returns the optional "err" type.
+    pub fn err(&self) -> Option<&Type> {
+        self.err.as_ref()
+    }
+}
+
+impl FlagsType {
+    /// Returns the flag names.
+    pub fn names(&self) -> &[Box<str>] {
+        &self.flags
+    }
+}
+
+impl HandleType {
+    /// Returns the resource name.
+    pub fn resource(&self) -> &str {
+        &self.resource
+    }
+
+    /// Returns true for a borrow handle type and false for an own handle
+    /// type.
+    pub fn is_borrow(&self) -> bool {
+        self.borrow
+    }
+}
+
+impl StreamType {
+    /// Returns the optional element type.
+    pub fn element(&self) -> Option<&Type> {
+        self.element.as_ref()
+    }
+}
+
+impl FutureType {
+    /// Returns the optional payload type.
+    pub fn payload(&self) -> Option<&Type> {
+        self.payload.as_ref()
+    }
+}
+
+impl PartialEq for Type {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
 impl WasmType for Type {
+    fn type_name(&self) -> Option<Cow<'_, str>> {
+        self.name().map(Into::into)
+    }
+
     fn kind(&self) -> WasmTypeKind {
         match self.0 {
             TypeEnum::Simple(simple) => simple.0,
@@ -199,6 +490,10 @@ impl WasmType for Type {
             TypeEnum::Option(_) => WasmTypeKind::Option,
             TypeEnum::Result(_) => WasmTypeKind::Result,
             TypeEnum::Flags(_) => WasmTypeKind::Flags,
+            TypeEnum::Handle(ref handle) if handle.borrow => WasmTypeKind::Borrow,
+            TypeEnum::Handle(_) => WasmTypeKind::Own,
+            TypeEnum::Stream(_) => WasmTypeKind::Stream,
+            TypeEnum::Future(_) => WasmTypeKind::Future,
         }
     }
 
@@ -207,7 +502,11 @@ impl WasmType for Type {
         Some(list.element.clone())
     }
 
-    fn record_fields(&self) -> Box<dyn Iterator<Item = (Cow<str>, Self)> + '_> {
+    fn list_length(&self) -> Option<u32> {
+        maybe_unwrap!(&self.0, TypeEnum::List)?.length
+    }
+
+    fn record_fields(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, Self)> + '_> {
         let TypeEnum::Record(record) = &self.0 else {
             return Box::new(std::iter::empty());
         };
@@ -226,7 +525,7 @@ impl WasmType for Type {
         Box::new(tuple.elements.iter().cloned())
     }
 
-    fn variant_cases(&self) -> Box<dyn Iterator<Item = (Cow<str>, Option<Self>)> + '_> {
+    fn variant_cases(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, Option<Self>)> + '_> {
         let TypeEnum::Variant(variant) = &self.0 else {
             return Box::new(std::iter::empty());
         };
@@ -238,7 +537,7 @@ impl WasmType for Type {
         )
     }
 
-    fn enum_cases(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn enum_cases(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         let TypeEnum::Enum(enum_) = &self.0 else {
             return Box::new(std::iter::empty());
         };
@@ -255,12 +554,27 @@ impl WasmType for Type {
         Some((result.ok.clone(), result.err.clone()))
     }
 
-    fn flags_names(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn flags_names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         let TypeEnum::Flags(flags) = &self.0 else {
             return Box::new(std::iter::empty());
         };
         Box::new(flags.flags.iter().map(|name| name.as_ref().into()))
     }
+
+    fn resource_name(&self) -> Option<Cow<'_, str>> {
+        let handle = maybe_unwrap!(&self.0, TypeEnum::Handle)?;
+        Some(handle.resource.as_ref().into())
+    }
+
+    fn stream_element_type(&self) -> Option<Option<Self>> {
+        let stream = maybe_unwrap!(&self.0, TypeEnum::Stream)?;
+        Some(stream.element.clone())
+    }
+
+    fn future_payload_type(&self) -> Option<Option<Self>> {
+        let future = maybe_unwrap!(&self.0, TypeEnum::Future)?;
+        Some(future.payload.clone())
+    }
 }
 
 impl std::fmt::Display for Type {
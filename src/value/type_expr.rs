@@ -0,0 +1,383 @@
+use std::{collections::HashSet, str::FromStr};
+
+use crate::{lex, ty::WasmTypeKind};
+
+use super::Type;
+
+/// Parses an anonymous WIT type expression, as written by
+/// [`DisplayType`](crate::fmt::DisplayType).
+/// ```
+/// # use wasm_wave::value::Type;
+/// let ty: Type = "list<tuple<u8, string>>".parse().unwrap();
+/// assert_eq!(ty, Type::list(Type::tuple([Type::U8, Type::STRING]).unwrap()));
+/// assert_eq!(ty.to_string(), "list<tuple<u8, string>>");
+/// ```
+impl FromStr for Type {
+    type Err = TypeParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = TypeParser { src: s, pos: 0 };
+        let ty = parser.parse_type()?;
+        parser.expect("")?;
+        Ok(ty)
+    }
+}
+
+/// An error from parsing a [`Type`] from a WIT type expression.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum TypeParseError {
+    /// Unexpected input
+    #[error("expected {expected}, got {got} at {pos}")]
+    Unexpected {
+        /// The byte offset of the unexpected input
+        pos: usize,
+        /// A description of what was expected
+        expected: String,
+        /// A description of the unexpected input
+        got: String,
+    },
+    /// A record, tuple, variant, enum, or flags type without any items
+    #[error("{kind} must not be empty at {pos}")]
+    Empty {
+        /// The byte offset of the type
+        pos: usize,
+        /// The kind of type
+        kind: WasmTypeKind,
+    },
+    /// A field, case, or flag name that was already given
+    #[error("duplicate name `{name}` at {pos}")]
+    DuplicateName {
+        /// The byte offset of the name
+        pos: usize,
+        /// The duplicate name
+        name: String,
+    },
+}
+
+struct TypeParser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> TypeParser<'a> {
+    fn parse_type(&mut self) -> Result<Type, TypeParseError> {
+        let (pos, name) = self.next();
+        Ok(match name {
+            "bool" => Type::BOOL,
+            "s8" => Type::S8,
+            "s16" => Type::S16,
+            "s32" => Type::S32,
+            "s64" => Type::S64,
+            "u8" => Type::U8,
+            "u16" => Type::U16,
+            "u32" => Type::U32,
+            "u64" => Type::U64,
+            "float32" | "f32" => Type::FLOAT32,
+            "float64" | "f64" => Type::FLOAT64,
+            "char" => Type::CHAR,
+            "string" => Type::STRING,
+            "error-context" => Type::ERROR_CONTEXT,
+            "list" => {
+                self.expect("<")?;
+                let element = self.parse_type()?;
+                if !self.eat(",") {
+                    self.expect(">")?;
+                    return Ok(Type::list(element));
+                }
+                let (length_pos, length) = self.next();
+                let length = length
+                    .parse()
+                    .map_err(|_| unexpected(length_pos, length, "list length"))?;
+                self.expect(">")?;
+                Type::fixed_list(element, length).ok_or(TypeParseError::Empty {
+                    pos,
+                    kind: WasmTypeKind::List,
+                })?
+            }
+            "option" => {
+                self.expect("<")?;
+                let some = self.parse_type()?;
+                self.expect(">")?;
+                Type::option(some)
+            }
+            "result" => {
+                if !self.eat("<") {
+                    return Ok(Type::result(None, None));
+                }
+                let ok = if self.eat("_") {
+                    self.expect(",")?;
+                    None
+                } else {
+                    let ok = self.parse_type()?;
+                    if !self.eat(",") {
+                        self.expect(">")?;
+                        return Ok(Type::result(Some(ok), None));
+                    }
+                    Some(ok)
+                };
+                let err = self.parse_type()?;
+                self.expect(">")?;
+                Type::result(ok, Some(err))
+            }
+            "tuple" => {
+                let elements = self.parse_seq("<", ">", Self::parse_type)?;
+                Type::tuple(elements).ok_or(TypeParseError::Empty {
+                    pos,
+                    kind: WasmTypeKind::Tuple,
+                })?
+            }
+            "record" => {
+                let fields = self.parse_seq("{", "}", |p| {
+                    let name = p.parse_label()?;
+                    p.expect(":")?;
+                    Ok((name, p.parse_type()?))
+                })?;
+                check_names(fields.iter().map(|(name, _)| *name))?;
+                let fields = fields.into_iter().map(|((_, name), ty)| (name, ty));
+                Type::record(fields).ok_or(TypeParseError::Empty {
+                    pos,
+                    kind: WasmTypeKind::Record,
+                })?
+            }
+            "variant" => {
+                let cases = self.parse_seq("{", "}", |p| {
+                    let name = p.parse_label()?;
+                    let payload = if p.eat("(") {
+                        let ty = p.parse_type()?;
+                        p.expect(")")?;
+                        Some(ty)
+                    } else {
+                        None
+                    };
+                    Ok((name, payload))
+                })?;
+                check_names(cases.iter().map(|(name, _)| *name))?;
+                let cases = cases.into_iter().map(|((_, name), ty)| (name, ty));
+                Type::variant(cases).ok_or(TypeParseError::Empty {
+                    pos,
+                    kind: WasmTypeKind::Variant,
+                })?
+            }
+            "stream" | "future" => {
+                let inner = if self.eat("<") {
+                    let ty = self.parse_type()?;
+                    self.expect(">")?;
+                    Some(ty)
+                } else {
+                    None
+                };
+                if name == "stream" {
+                    Type::stream(inner)
+                } else {
+                    Type::future(inner)
+                }
+            }
+            "own" | "borrow" => {
+                self.expect("<")?;
+                let (_, resource) = self.parse_label()?;
+                self.expect(">")?;
+                if name == "own" {
+                    Type::own(resource)
+                } else {
+                    Type::borrow(resource)
+                }
+            }
+            "enum" | "flags" => {
+                let names = self.parse_seq("{", "}", Self::parse_label)?;
+                check_names(names.iter().copied())?;
+                let names = names.into_iter().map(|(_, name)| name);
+                let ty = if name == "enum" {
+                    Type::enum_ty(names)
+                } else {
+                    Type::flags(names)
+                };
+                ty.ok_or_else(|| TypeParseError::Empty {
+                    pos,
+                    kind: if name == "enum" {
+                        WasmTypeKind::Enum
+                    } else {
+                        WasmTypeKind::Flags
+                    },
+                })?
+            }
+            _ => return Err(unexpected(pos, name, "type")),
+        })
+    }
+
+    // Parses a comma-separated sequence of items between `open` and `close`.
+    // A trailing comma is permitted.
+    fn parse_seq<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        mut parse_item: impl FnMut(&mut Self) -> Result<T, TypeParseError>,
+    ) -> Result<Vec<T>, TypeParseError> {
+        self.expect(open)?;
+        let mut items = vec![];
+        while !self.eat(close) {
+            items.push(parse_item(self)?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    // Parses a label, returning its position and name without any `%`
+    // prefix.
+    fn parse_label(&mut self) -> Result<(usize, &'a str), TypeParseError> {
+        let (pos, token) = self.next();
+        let label = token.strip_prefix('%').unwrap_or(token);
+        if !lex::is_label(label) {
+            return Err(unexpected(pos, token, "label"));
+        }
+        Ok((pos, label))
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), TypeParseError> {
+        let (pos, token) = self.next();
+        if token != expected {
+            let expected = match expected {
+                "" => "end of input".to_string(),
+                _ => format!("`{expected}`"),
+            };
+            return Err(unexpected(pos, token, expected));
+        }
+        Ok(())
+    }
+
+    fn eat(&mut self, expected: &str) -> bool {
+        let start = self.pos;
+        if self.next().1 == expected {
+            return true;
+        }
+        self.pos = start;
+        false
+    }
+
+    // Returns the position and text of the next token, which is a name, a
+    // single punctuation char, or empty at the end of input.
+    fn next(&mut self) -> (usize, &'a str) {
+        let rest = self.src[self.pos..].trim_start();
+        let start = self.src.len() - rest.len();
+        let len = match rest.chars().next() {
+            Some(ch) if ch.is_ascii_alphanumeric() || ch == '%' => rest[1..]
+                .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-'))
+                .map_or(rest.len(), |len| len + 1),
+            Some(ch) => ch.len_utf8(),
+            None => 0,
+        };
+        self.pos = start + len;
+        (start, &rest[..len])
+    }
+}
+
+// Returns an error if any of the given names are duplicates.
+fn check_names<'a>(names: impl Iterator<Item = (usize, &'a str)>) -> Result<(), TypeParseError> {
+    let mut seen = HashSet::new();
+    for (pos, name) in names {
+        if !seen.insert(name) {
+            return Err(TypeParseError::DuplicateName {
+                pos,
+                name: name.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+fn unexpected(pos: usize, got: &str, expected: impl Into<String>) -> TypeParseError {
+    let got = match got {
+        "" => "end of input".to_string(),
+        _ => format!("`{got}`"),
+    };
+    TypeParseError::Unexpected {
+        pos,
+        expected: expected.into(),
+        got,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        for input in [
+            "bool",
+            "s8",
+            "u64",
+            "float32",
+            "float64",
+            "char",
+            "string",
+            "list<u8>",
+            "list<list<string>>",
+            "list<u8, 4>",
+            "list<list<float32, 3>, 3>",
+            "option<option<char>>",
+            "tuple<u8>",
+            "tuple<u8, list<string>, option<s32>>",
+            "result",
+            "result<u8>",
+            "result<_, string>",
+            "result<u8, string>",
+            "record { a: u8, b-c: list<string>, %none: bool }",
+            "variant { a, b(u8), c(tuple<s8, s16>) }",
+            "enum { north, SOUTH }",
+            "flags { read, write }",
+            "own<file>",
+            "stream",
+            "stream<u8>",
+            "future",
+            "future<result<string, error-context>>",
+            "error-context",
+            "borrow<input-stream>",
+            "list<record { xs: list<variant { a(result<_, enum { x }>) }> }>",
+        ] {
+            let ty: Type = input.parse().unwrap();
+            let expected = input.replace("%none", "none");
+            assert_eq!(ty.to_string(), expected);
+            assert_eq!(expected.parse::<Type>().unwrap(), ty);
+        }
+    }
+
+    #[test]
+    fn whitespace_aliases_and_trailing_commas() {
+        for (input, expected) in [
+            (" list < f32 > ", "list<float32>"),
+            ("tuple<f64,u8,>", "tuple<float64, u8>"),
+            ("record{a:u8,}", "record { a: u8 }"),
+            ("enum {\n  %true,\n  x,\n}", "enum { true, x }"),
+        ] {
+            let ty: Type = input.parse().unwrap();
+            assert_eq!(ty.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        for (input, expected) in [
+            ("", "expected type, got end of input at 0"),
+            ("u128", "expected type, got `u128` at 0"),
+            ("list<u8", "expected `>`, got end of input at 7"),
+            ("list<u8>>", "expected end of input, got `>` at 8"),
+            ("result<_>", "expected `,`, got `>` at 8"),
+            ("tuple<>", "tuple must not be empty at 0"),
+            ("list<u8, 0>", "list must not be empty at 0"),
+            ("list<u8, n>", "expected list length, got `n` at 9"),
+            ("record {}", "record must not be empty at 0"),
+            ("record { a: u8, a: u8 }", "duplicate name `a` at 16"),
+            ("flags { a_b }", "expected `}`, got `_` at 9"),
+            ("enum { aB }", "expected label, got `aB` at 7"),
+            ("variant { a(u8 }", "expected `)`, got `}` at 15"),
+            ("option<é>", "expected type, got `é` at 7"),
+        ] {
+            let err = input.parse::<Type>().unwrap_err();
+            assert_eq!(err.to_string(), expected, "for {input:?}");
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use super::{Enum, Flags, Handle, List, Record, Tuple, Value, ValueEnum, Variant};
+use crate::{canonicalize_nan32, canonicalize_nan64};
+
+/// A borrowed view of a [`Value`], returned by [`Value::view`].
+///
+/// Unlike the [`WasmValue`](crate::WasmValue) `unwrap_*` methods, matching
+/// on a view never panics.
+/// ```
+/// # use wasm_wave::value::{Type, Value, ValueRef};
+/// let ty = Type::record([("id", Type::U32), ("name", Type::option(Type::STRING))]).unwrap();
+/// let val: Value = wasm_wave::from_str(&ty, r#"{id: 7, name: some("x")}"#).unwrap();
+/// let ValueRef::Record(record) = val.view() else { unreachable!() };
+/// assert_eq!(record.get("id").and_then(Value::as_u32), Some(7));
+/// let name = record.get("name").and_then(Value::as_option).flatten();
+/// assert_eq!(name.and_then(Value::as_string), Some("x"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[allow(missing_docs)]
+#[non_exhaustive]
+pub enum ValueRef<'a> {
+    Bool(bool),
+    S8(i8),
+    U8(u8),
+    S16(i16),
+    U16(u16),
+    S32(i32),
+    U32(u32),
+    S64(i64),
+    U64(u64),
+    Float32(f32),
+    Float64(f64),
+    Char(char),
+    String(&'a str),
+    List(&'a List),
+    Record(&'a Record),
+    Tuple(&'a Tuple),
+    Variant(&'a Variant),
+    Enum(&'a Enum),
+    Option(Option<&'a Value>),
+    Result(Result<Option<&'a Value>, Option<&'a Value>>),
+    Flags(&'a Flags),
+    /// An own, borrow, stream, future or error-context handle.
+    Handle(&'a Handle),
+}
+
+macro_rules! impl_as_copy {
+    ($(($case:ident, $ty:ty, $as:ident)),*) => {
+        $(
+            #[doc = concat!("Returns the value if it is a `", stringify!($ty), "`.")]
+            pub fn $as(&self) -> Option<$ty> {
+                match self.view() {
+                    ValueRef::$case(val) => Some(val),
+                    _ => None,
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_as_ref {
+    ($(($case:ident, $ty:ty, $as:ident, $kind:literal)),*) => {
+        $(
+            #[doc = concat!("Returns the value if it is a ", $kind, ".")]
+            pub fn $as(&self) -> Option<&$ty> {
+                match self.view() {
+                    ValueRef::$case(val) => Some(val),
+                    _ => None,
+                }
+            }
+        )*
+    };
+}
+
+impl Value {
+    /// Returns a borrowed view of this value.
+    pub fn view(&self) -> ValueRef<'_> {
+        match &self.0 {
+            ValueEnum::Bool(val) => ValueRef::Bool(*val),
+            ValueEnum::S8(val) => ValueRef::S8(*val),
+            ValueEnum::U8(val) => ValueRef::U8(*val),
+            ValueEnum::S16(val) => ValueRef::S16(*val),
+            ValueEnum::U16(val) => ValueRef::U16(*val),
+            ValueEnum::S32(val) => ValueRef::S32(*val),
+            ValueEnum::U32(val) => ValueRef::U32(*val),
+            ValueEnum::S64(val) => ValueRef::S64(*val),
+            ValueEnum::U64(val) => ValueRef::U64(*val),
+            ValueEnum::Float32(val) => ValueRef::Float32(canonicalize_nan32(*val)),
+            ValueEnum::Float64(val) => ValueRef::Float64(canonicalize_nan64(*val)),
+            ValueEnum::Char(val) => ValueRef::Char(*val),
+            ValueEnum::String(val) => ValueRef::String(val),
+            ValueEnum::List(list) => ValueRef::List(list),
+            ValueEnum::Record(record) => ValueRef::Record(record),
+            ValueEnum::Tuple(tuple) => ValueRef::Tuple(tuple),
+            ValueEnum::Variant(variant) => ValueRef::Variant(variant),
+            ValueEnum::Enum(enum_) => ValueRef::Enum(enum_),
+            ValueEnum::Option(option) => ValueRef::Option(option.value.as_deref()),
+            ValueEnum::Result(result) => ValueRef::Result(match &result.value {
+                Ok(val) => Ok(val.as_deref()),
+                Err(val) => Err(val.as_deref()),
+            }),
+            ValueEnum::Flags(flags) => ValueRef::Flags(flags),
+            ValueEnum::Handle(handle) => ValueRef::Handle(handle),
+        }
+    }
+
+    impl_as_copy!(
+        (Bool, bool, as_bool),
+        (S8, i8, as_s8),
+        (U8, u8, as_u8),
+        (S16, i16, as_s16),
+        (U16, u16, as_u16),
+        (S32, i32, as_s32),
+        (U32, u32, as_u32),
+        (S64, i64, as_s64),
+        (U64, u64, as_u64),
+        (Float32, f32, as_float32),
+        (Float64, f64, as_float64),
+        (Char, char, as_char),
+        (String, &str, as_string)
+    );
+
+    impl_as_ref!(
+        (List, List, as_list, "list"),
+        (Record, Record, as_record, "record"),
+        (Tuple, Tuple, as_tuple, "tuple"),
+        (Variant, Variant, as_variant, "variant"),
+        (Enum, Enum, as_enum, "enum"),
+        (Flags, Flags, as_flags, "flags"),
+        (Handle, Handle, as_handle, "handle")
+    );
+
+    /// Returns the optional payload if the value is an option.
+    pub fn as_option(&self) -> Option<Option<&Self>> {
+        match self.view() {
+            ValueRef::Option(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Returns the optional "ok" or "err" payload if the value is a result.
+    pub fn as_result(&self) -> Option<Result<Option<&Self>, Option<&Self>>> {
+        match self.view() {
+            ValueRef::Result(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+impl List {
+    /// Returns the elements.
+    pub fn elements(&self) -> &[Value] {
+        &self.elements
+    }
+}
+
+impl Record {
+    /// Returns an iterator of the field names and values, in type order.
+    pub fn fields(&self) -> impl ExactSizeIterator<Item = (&str, &Value)> {
+        self.ty
+            .fields
+            .iter()
+            .map(|(name, _)| name.as_ref())
+            .zip(&self.fields)
+    }
+
+    /// Returns the value of the field with the given name, if any.
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.fields()
+            .find_map(|(field, val)| (field == name).then_some(val))
+    }
+}
+
+impl Tuple {
+    /// Returns the elements.
+    pub fn elements(&self) -> &[Value] {
+        &self.elements
+    }
+}
+
+impl Variant {
+    /// Returns the case name.
+    pub fn case_name(&self) -> &str {
+        &self.ty.cases[self.case].0
+    }
+
+    /// Returns the case payload, if any.
+    pub fn payload(&self) -> Option<&Value> {
+        self.payload.as_deref()
+    }
+}
+
+impl Enum {
+    /// Returns the case name.
+    pub fn case_name(&self) -> &str {
+        &self.ty.cases[self.case]
+    }
+}
+
+impl Flags {
+    /// Returns an iterator of the names of the set flags, in type order.
+    pub fn names(&self) -> impl ExactSizeIterator<Item = &str> {
+        self.flags.iter().map(|idx| self.ty.flags[*idx].as_ref())
+    }
+
+    /// Returns true if the flag with the given name is set.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names().any(|flag| flag == name)
+    }
+}
+
+impl Handle {
+    /// Returns the opaque handle id.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value::Type;
+
+    #[test]
+    fn view_matches_value() {
+        let ty: Type = "record { xs: list<u8>, pos: tuple<s8, char>, shape: variant { dot, \
+            circle(float32) }, dir: enum { up, down }, r: result<_, string>, \
+            perms: flags { read, write, exec }, f: own<file> }"
+            .parse()
+            .unwrap();
+        let input = "{xs: [1, 2], pos: (-1, 'x'), shape: circle(nan), dir: down, \
+            r: err(\"oops\"), perms: {exec, read}, f: file(9)}";
+        let val: Value = crate::from_str(&ty, input).unwrap();
+        let record = val.as_record().unwrap();
+        assert_eq!(
+            record.fields().map(|(name, _)| name).collect::<Vec<_>>(),
+            ["xs", "pos", "shape", "dir", "r", "perms", "f"]
+        );
+        assert!(record.get("missing").is_none());
+
+        let xs = record.get("xs").and_then(Value::as_list).unwrap();
+        assert_eq!(xs.elements(), [Value::from(1u8), Value::from(2u8)]);
+
+        let pos = record.get("pos").and_then(Value::as_tuple).unwrap();
+        assert_eq!(pos.elements()[0].view(), ValueRef::S8(-1));
+        assert_eq!(pos.elements()[1].as_char(), Some('x'));
+        assert_eq!(pos.elements()[1].as_u8(), None);
+
+        let shape = record.get("shape").and_then(Value::as_variant).unwrap();
+        assert_eq!(shape.case_name(), "circle");
+        let radius = shape.payload().and_then(Value::as_float32).unwrap();
+        assert!(radius.is_nan());
+
+        let dir = record.get("dir").and_then(Value::as_enum).unwrap();
+        assert_eq!(dir.case_name(), "down");
+
+        let r = record.get("r").and_then(Value::as_result).unwrap();
+        assert_eq!(r.unwrap_err().and_then(Value::as_string), Some("oops"));
+
+        let perms = record.get("perms").and_then(Value::as_flags).unwrap();
+        assert_eq!(perms.names().collect::<Vec<_>>(), ["read", "exec"]);
+        assert!(perms.contains("exec") && !perms.contains("write"));
+
+        let f = record.get("f").and_then(Value::as_handle).unwrap();
+        assert_eq!(f.id(), 9);
+
+        assert_eq!(val.as_option(), None);
+        assert!(matches!(val.view(), ValueRef::Record(_)));
+    }
+}
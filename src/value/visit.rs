@@ -0,0 +1,107 @@
+use super::{Type, TypeRef};
+
+/// A visitor for recursively traversing a [`Type`].
+///
+/// The default [`visit_type`](Self::visit_type) calls [`walk_type`] to visit
+/// each child type; implementations that override it may call [`walk_type`]
+/// themselves to continue the traversal.
+/// ```
+/// # use wasm_wave::value::{walk_type, Type, TypeRef, TypeVisitor};
+/// // Collects the names of all record fields.
+/// #[derive(Default)]
+/// struct FieldNames<'a>(Vec<&'a str>);
+///
+/// impl<'a> TypeVisitor<'a> for FieldNames<'a> {
+///     fn visit_type(&mut self, ty: &'a Type) {
+///         if let TypeRef::Record(record) = ty.view() {
+///             self.0.extend(record.fields().iter().map(|(name, _)| &**name));
+///         }
+///         walk_type(self, ty);
+///     }
+/// }
+///
+/// let ty: Type = "list<record { id: u32, owner: option<record { name: string }> }>"
+///     .parse()
+///     .unwrap();
+/// let mut names = FieldNames::default();
+/// names.visit_type(&ty);
+/// assert_eq!(names.0, ["id", "owner", "name"]);
+/// ```
+pub trait TypeVisitor<'a> {
+    /// Visits the given type.
+    fn visit_type(&mut self, ty: &'a Type) {
+        walk_type(self, ty)
+    }
+}
+
+/// Calls [`TypeVisitor::visit_type`] for each child type of the given type,
+/// in order.
+pub fn walk_type<'a, V: TypeVisitor<'a> + ?Sized>(visitor: &mut V, ty: &'a Type) {
+    match ty.view() {
+        TypeRef::Simple(_) | TypeRef::Enum(_) | TypeRef::Flags(_) | TypeRef::Handle(_) => (),
+        TypeRef::List(list) => visitor.visit_type(list.element()),
+        TypeRef::Record(record) => {
+            for (_, ty) in record.fields() {
+                visitor.visit_type(ty);
+            }
+        }
+        TypeRef::Tuple(tuple) => {
+            for ty in tuple.elements() {
+                visitor.visit_type(ty);
+            }
+        }
+        TypeRef::Variant(variant) => {
+            for ty in variant.cases().iter().filter_map(|(_, ty)| ty.as_ref()) {
+                visitor.visit_type(ty);
+            }
+        }
+        TypeRef::Option(option) => visitor.visit_type(option.some()),
+        TypeRef::Result(result) => {
+            for ty in [result.ok(), result.err()].into_iter().flatten() {
+                visitor.visit_type(ty);
+            }
+        }
+        TypeRef::Stream(stream) => {
+            if let Some(ty) = stream.element() {
+                visitor.visit_type(ty);
+            }
+        }
+        TypeRef::Future(future) => {
+            if let Some(ty) = future.payload() {
+                visitor.visit_type(ty);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WasmType;
+
+    #[test]
+    fn visits_all_types_in_order() {
+        struct Kinds(Vec<String>);
+
+        impl<'a> TypeVisitor<'a> for Kinds {
+            fn visit_type(&mut self, ty: &'a Type) {
+                self.0.push(ty.kind().to_string());
+                walk_type(self, ty);
+            }
+        }
+
+        let ty: Type = "tuple<variant { a, b(list<u8, 2>) }, result<_, option<string>>, \
+            future<stream<own<file>>>, enum { x }>"
+            .parse()
+            .unwrap();
+        let mut kinds = Kinds(vec![]);
+        kinds.visit_type(&ty);
+        assert_eq!(
+            kinds.0,
+            [
+                "tuple", "variant", "list", "u8", "result", "option", "string", "future", "stream",
+                "own", "enum",
+            ]
+        );
+    }
+}
@@ -1,10 +1,15 @@
 use wit_parser::{
-    Enum, Flags, Function, Record, Resolve, Result_, Tuple, Type, TypeDefKind, TypeId, Variant,
+    Enum, Flags, Function, Handle, Record, Resolve, Result_, Stream, Tuple, Type, TypeDefKind,
+    TypeId, TypeOwner, Variant,
 };
 
 use crate::value;
 
 /// Resolves a [`value::Type`] from the given [`wit_parser::Resolve`] and [`TypeId`].
+///
+/// Types resolved from named type definitions, including those nested in
+/// other types, are given the definition's name and the ID of the interface
+/// it is defined in, if any. See [`value::Type::with_name`].
 /// # Panics
 /// Panics if `type_id` is not valid in `resolve`.
 pub fn resolve_wit_type(
@@ -38,7 +43,7 @@ type ValueResult = Result<value::Type, value::ValueError>;
 
 impl<'a> TypeResolver<'a> {
     fn resolve_type_id(&self, type_id: TypeId) -> ValueResult {
-        self.resolve(&self.resolve.types.get(type_id).unwrap().kind)
+        self.resolve(&TypeDefKind::Type(Type::Id(type_id)))
     }
 
     fn resolve_type(&self, ty: Type) -> ValueResult {
@@ -58,13 +63,21 @@ impl<'a> TypeResolver<'a> {
             .collect()
     }
 
-    fn resolve(&self, mut kind: &'a TypeDefKind) -> ValueResult {
-        // Recursively resolve any type defs.
+    fn resolve<'b>(&self, mut kind: &'b TypeDefKind) -> ValueResult
+    where
+        'a: 'b,
+    {
+        // Recursively resolve any type defs, keeping the outermost name.
+        let mut named = None;
         while let &TypeDefKind::Type(Type::Id(id)) = kind {
-            kind = &self.resolve.types.get(id).unwrap().kind;
+            let type_def = self.resolve.types.get(id).unwrap();
+            if named.is_none() && type_def.name.is_some() {
+                named = Some(type_def);
+            }
+            kind = &type_def.kind;
         }
 
-        match kind {
+        let ty = match kind {
             TypeDefKind::Record(record) => self.resolve_record(record),
             TypeDefKind::Flags(flags) => self.resolve_flags(flags),
             TypeDefKind::Tuple(tuple) => self.resolve_tuple(tuple),
@@ -73,6 +86,9 @@ impl<'a> TypeResolver<'a> {
             TypeDefKind::Option(some_type) => self.resolve_option(some_type),
             TypeDefKind::Result(result) => self.resolve_result(result),
             TypeDefKind::List(element_type) => self.resolve_list(element_type),
+            TypeDefKind::Handle(handle) => self.resolve_handle(handle),
+            TypeDefKind::Stream(stream) => self.resolve_stream(stream),
+            TypeDefKind::Future(payload) => self.resolve_future(payload),
             TypeDefKind::Type(Type::Bool) => Ok(value::Type::BOOL),
             TypeDefKind::Type(Type::U8) => Ok(value::Type::U8),
             TypeDefKind::Type(Type::U16) => Ok(value::Type::U16),
@@ -91,7 +107,15 @@ impl<'a> TypeResolver<'a> {
                 "unsupported type {}",
                 other.as_str()
             ))),
-        }
+        }?;
+        let Some(type_def) = named else {
+            return Ok(ty);
+        };
+        let interface = match type_def.owner {
+            TypeOwner::Interface(id) => self.resolve.id_of(id),
+            _ => None,
+        };
+        Ok(ty.with_name(type_def.name.as_deref().unwrap(), interface.as_deref()))
     }
 
     fn resolve_record(&self, record: &Record) -> ValueResult {
@@ -151,6 +175,37 @@ impl<'a> TypeResolver<'a> {
         let element_type = self.resolve_type(*element_type)?;
         Ok(value::Type::list(element_type))
     }
+
+    fn resolve_stream(&self, stream: &Stream) -> ValueResult {
+        if stream.end.is_some() {
+            return Err(value::ValueError::InvalidType(
+                "unsupported stream end type".into(),
+            ));
+        }
+        let element = stream.element.map(|ty| self.resolve_type(ty)).transpose()?;
+        Ok(value::Type::stream(element))
+    }
+
+    fn resolve_future(&self, payload: &Option<Type>) -> ValueResult {
+        let payload = payload.map(|ty| self.resolve_type(ty)).transpose()?;
+        Ok(value::Type::future(payload))
+    }
+
+    fn resolve_handle(&self, handle: &Handle) -> ValueResult {
+        let (Handle::Own(mut id) | Handle::Borrow(mut id)) = *handle;
+        // Follow any aliases to the resource definition.
+        while let TypeDefKind::Type(Type::Id(next)) = self.resolve.types[id].kind {
+            id = next;
+        }
+        let resource = self.resolve.types[id]
+            .name
+            .as_deref()
+            .ok_or_else(|| value::ValueError::InvalidType("resource must be named".into()))?;
+        Ok(match handle {
+            Handle::Own(_) => value::Type::own(resource),
+            Handle::Borrow(_) => value::Type::borrow(resource),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -158,6 +213,7 @@ mod tests {
     use wit_parser::UnresolvedPackage;
 
     use super::*;
+    use crate::WasmType;
 
     #[test]
     fn resolve_wit_type_smoke_test() {
@@ -215,4 +271,124 @@ mod tests {
             assert_eq!(ty.to_string(), expected_display, "for {function:?}");
         }
     }
+
+    #[test]
+    fn resolve_wit_type_names() {
+        let unresolved = UnresolvedPackage::parse(
+            "test.wit".as_ref(),
+            r#"
+            package test:types
+            interface types {
+                enum color { red, green }
+                type colors = list<color>
+                type shade = color
+                record user-profile {
+                    name: string,
+                    favorites: colors,
+                    shade: shade,
+                }
+            }
+        "#,
+        )
+        .unwrap();
+        let mut resolve = Resolve::new();
+        resolve.push(unresolved).unwrap();
+
+        let type_id = |name: &str| {
+            resolve
+                .types
+                .iter()
+                .find_map(|(id, def)| (def.name.as_deref() == Some(name)).then_some(id))
+                .unwrap()
+        };
+        let ty = resolve_wit_type(&resolve, type_id("user-profile")).unwrap();
+        assert_eq!(ty.name(), Some("user-profile"));
+        assert_eq!(ty.interface(), Some("test:types/types"));
+        assert_eq!(format!("{ty:#}"), "user-profile");
+        assert_eq!(
+            ty.to_string(),
+            "record { name: string, favorites: list<enum { red, green }>, shade: enum { red, green } }"
+        );
+
+        let fields = ty.record_fields().collect::<Vec<_>>();
+        assert_eq!(fields[0].1.name(), None);
+        assert_eq!(format!("{:#}", fields[1].1), "colors");
+        // An alias keeps its own name
+        assert_eq!(format!("{:#}", fields[2].1), "shade");
+
+        let ty = resolve_wit_type(&resolve, type_id("colors")).unwrap();
+        assert_eq!(format!("{ty:#}"), "colors");
+        let element = ty.list_element_type().unwrap();
+        assert_eq!(format!("{element:#}"), "color");
+        assert_eq!(format!("{:#}", crate::fmt::DisplayType(ty)), "colors");
+    }
+
+    #[test]
+    fn resolve_wit_async_types() {
+        let unresolved = UnresolvedPackage::parse(
+            "test.wit".as_ref(),
+            r#"
+            package test:types
+            interface types {
+                read: func(len: u32) -> stream<u8>
+                done: func() -> future
+                fetch: func(url: string) -> future<result<list<u8>, string>>
+            }
+        "#,
+        )
+        .unwrap();
+        let mut resolve = Resolve::new();
+        resolve.push(unresolved).unwrap();
+
+        for (func_name, expected_display) in [
+            ("read", "func(len: u32) -> stream<u8>"),
+            ("done", "func() -> future"),
+            (
+                "fetch",
+                "func(url: string) -> future<result<list<u8>, string>>",
+            ),
+        ] {
+            let function = resolve
+                .interfaces
+                .iter()
+                .flat_map(|(_, i)| &i.functions)
+                .find_map(|(name, function)| (name == func_name).then_some(function))
+                .unwrap();
+            let ty = resolve_wit_func_type(&resolve, function).unwrap();
+            assert_eq!(ty.to_string(), expected_display, "for {function:?}");
+        }
+    }
+
+    #[test]
+    fn resolve_wit_handles() {
+        let unresolved = UnresolvedPackage::parse(
+            "test.wit".as_ref(),
+            r#"
+            package test:types
+            interface types {
+                resource file
+                type fd = file
+                open: func(path: string) -> file
+                read: func(f: borrow<fd>, len: u32) -> list<u8>
+            }
+        "#,
+        )
+        .unwrap();
+        let mut resolve = Resolve::new();
+        resolve.push(unresolved).unwrap();
+
+        for (func_name, expected_display) in [
+            ("open", "func(path: string) -> own<file>"),
+            ("read", "func(f: borrow<file>, len: u32) -> list<u8>"),
+        ] {
+            let function = resolve
+                .interfaces
+                .iter()
+                .flat_map(|(_, i)| &i.functions)
+                .find_map(|(name, function)| (name == func_name).then_some(function))
+                .unwrap();
+            let ty = resolve_wit_func_type(&resolve, function).unwrap();
+            assert_eq!(ty.to_string(), expected_display, "for {function:?}");
+        }
+    }
 }
@@ -44,7 +44,7 @@ impl WasmType for component::Type {
         Some(maybe_unwrap!(self, Self::List)?.ty())
     }
 
-    fn record_fields(&self) -> Box<dyn Iterator<Item = (Cow<str>, Self)> + '_> {
+    fn record_fields(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, Self)> + '_> {
         let Self::Record(record) = self else {
             return Box::new(std::iter::empty());
         };
@@ -58,14 +58,14 @@ impl WasmType for component::Type {
         Box::new(tuple.types())
     }
 
-    fn variant_cases(&self) -> Box<dyn Iterator<Item = (Cow<str>, Option<Self>)> + '_> {
+    fn variant_cases(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, Option<Self>)> + '_> {
         let Self::Variant(variant) = self else {
             return Box::new(std::iter::empty());
         };
         Box::new(variant.cases().map(|case| (case.name.into(), case.ty)))
     }
 
-    fn enum_cases(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn enum_cases(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         let Self::Enum(enum_) = self else {
             return Box::new(std::iter::empty());
         };
@@ -81,7 +81,7 @@ impl WasmType for component::Type {
         Some((result.ok(), result.err()))
     }
 
-    fn flags_names(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn flags_names(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         let Self::Flags(flags) = self else {
             return Box::new(std::iter::empty());
         };
@@ -185,38 +185,38 @@ impl WasmValue for component::Val {
         let val = *unwrap_val!(self, Self::Float64, "float64");
         canonicalize_nan64(val)
     }
-    fn unwrap_string(&self) -> Cow<str> {
+    fn unwrap_string(&self) -> Cow<'_, str> {
         unwrap_val!(self, Self::String, "string").as_ref().into()
     }
-    fn unwrap_list(&self) -> Box<dyn Iterator<Item = Cow<Self>> + '_> {
+    fn unwrap_list(&self) -> Box<dyn Iterator<Item = Cow<'_, Self>> + '_> {
         let list = unwrap_val!(self, Self::List, "list");
         Box::new(list.iter().map(cow))
     }
-    fn unwrap_record(&self) -> Box<dyn Iterator<Item = (Cow<str>, Cow<Self>)> + '_> {
+    fn unwrap_record(&self) -> Box<dyn Iterator<Item = (Cow<'_, str>, Cow<'_, Self>)> + '_> {
         let record = unwrap_val!(self, Self::Record, "record");
         Box::new(record.fields().map(|(name, val)| (name.into(), cow(val))))
     }
-    fn unwrap_tuple(&self) -> Box<dyn Iterator<Item = Cow<Self>> + '_> {
+    fn unwrap_tuple(&self) -> Box<dyn Iterator<Item = Cow<'_, Self>> + '_> {
         let tuple = unwrap_val!(self, Self::Tuple, "tuple");
         Box::new(tuple.values().iter().map(cow))
     }
-    fn unwrap_variant(&self) -> (Cow<str>, Option<Cow<Self>>) {
+    fn unwrap_variant(&self) -> (Cow<'_, str>, Option<Cow<'_, Self>>) {
         let variant = unwrap_val!(self, Self::Variant, "variant");
         (variant.discriminant().into(), variant.payload().map(cow))
     }
-    fn unwrap_enum(&self) -> Cow<str> {
+    fn unwrap_enum(&self) -> Cow<'_, str> {
         unwrap_val!(self, Self::Enum, "enum").discriminant().into()
     }
-    fn unwrap_option(&self) -> Option<Cow<Self>> {
+    fn unwrap_option(&self) -> Option<Cow<'_, Self>> {
         unwrap_val!(self, Self::Option, "option").value().map(cow)
     }
-    fn unwrap_result(&self) -> Result<Option<Cow<Self>>, Option<Cow<Self>>> {
+    fn unwrap_result(&self) -> Result<Option<Cow<'_, Self>>, Option<Cow<'_, Self>>> {
         match unwrap_val!(self, Self::Result, "result").value() {
             Ok(val) => Ok(val.map(cow)),
             Err(val) => Err(val.map(cow)),
         }
     }
-    fn unwrap_flags(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+    fn unwrap_flags(&self) -> Box<dyn Iterator<Item = Cow<'_, str>> + '_> {
         let flags = unwrap_val!(self, Self::Flags, "flags");
         Box::new(flags.flags().map(Into::into))
     }
@@ -259,7 +259,7 @@ pub fn get_func_type(func: &component::Func, store: &impl wasmtime::AsContext) -
     }
 }
 
-fn cow<T: Clone>(t: &T) -> Cow<T> {
+fn cow<T: Clone>(t: &T) -> Cow<'_, T> {
     Cow::Borrowed(t)
 }
 
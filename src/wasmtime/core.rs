@@ -85,7 +85,7 @@ impl WasmValue for wasmtime::Val {
         canonicalize_nan64(val)
     }
 
-    fn unwrap_tuple(&self) -> Box<dyn Iterator<Item = Cow<Self>> + '_> {
+    fn unwrap_tuple(&self) -> Box<dyn Iterator<Item = Cow<'_, Self>> + '_> {
         let v = *unwrap_val!(self, Self::V128, "tuple");
         let low = v as i64;
         let high = (v >> 64) as i64;
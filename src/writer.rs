@@ -1,25 +1,156 @@
 //! Web Assembly Value Encoding writer.
 
-use std::{fmt::Debug, io::Write};
+use std::{borrow::Borrow, fmt::Debug};
 
 use thiserror::Error;
 
 use crate::{
+    fmt::Limits,
+    func::WasmFunc,
+    lex,
+    parser::flattenable,
     ty::{WasmType, WasmTypeKind},
     val::WasmValue,
 };
 
 /// A Web Assembly Value Encoding writer.
 ///
-/// Writes to the wrapped `W` writer.
+/// Writes to the wrapped `W` writer, which may be any [`std::io::Write`] or
+/// a [`std::fmt::Write`] wrapped in [`FmtWrite`].
 pub struct Writer<W> {
     inner: W,
+    hex_unsigned: bool,
+    escape: Escape,
+    flat_payloads: bool,
+    omit_none: bool,
+    pretty: bool,
+    indent: usize,
+    line_width: usize,
+    // The current nesting depth of multi-line sequences
+    depth: usize,
+    // The current column, counted in chars
+    column: usize,
+    // While measuring whether a sequence fits on a line, the number of chars
+    // that may still be written; nothing is written to `inner`
+    measuring: Option<usize>,
+    // Output limits for truncated display; see `fmt::DisplayTruncated`
+    limits: Option<Limits>,
+    // The current nesting depth of sequences, for `Limits::max_depth`
+    nesting: usize,
+    // The number of bytes written, for `Limits::max_bytes`
+    written: usize,
 }
 
-impl<W: Write> Writer<W> {
-    /// Returns a new Writer for the given [`std::io::Write`].
+impl<W: Output> Writer<W> {
+    /// Returns a new Writer for the given [`Output`].
+    /// ```
+    /// # use wasm_wave::{value::Value, writer::{FmtWrite, Writer}};
+    /// let mut s = String::new();
+    /// Writer::new(FmtWrite(&mut s)).write_value(&Value::from("hi")).unwrap();
+    /// assert_eq!(s, r#""hi""#);
+    /// ```
     pub fn new(w: W) -> Self {
-        Self { inner: w }
+        Self {
+            inner: w,
+            hex_unsigned: false,
+            escape: Escape::default(),
+            flat_payloads: false,
+            omit_none: true,
+            pretty: false,
+            indent: 2,
+            line_width: 80,
+            depth: 0,
+            column: 0,
+            measuring: None,
+            limits: None,
+            nesting: 0,
+            written: 0,
+        }
+    }
+
+    /// Sets whether unsigned integers are written in hex, e.g. `0xff`.
+    /// Defaults to false.
+    /// ```
+    /// # use wasm_wave::{value::Value, writer::Writer};
+    /// let mut buf = vec![];
+    /// Writer::new(&mut buf).hex_unsigned(true).write_value(&Value::from(255u8)).unwrap();
+    /// assert_eq!(buf, b"0xff");
+    /// ```
+    pub fn hex_unsigned(mut self, hex_unsigned: bool) -> Self {
+        self.hex_unsigned = hex_unsigned;
+        self
+    }
+
+    /// Sets which chars in char and string values are escaped. Defaults to
+    /// [`Escape::Printable`].
+    /// ```
+    /// # use wasm_wave::{value::Value, writer::{Escape, Writer}};
+    /// let mut buf = vec![];
+    /// Writer::new(&mut buf).escape(Escape::Ascii).write_value(&Value::from("é")).unwrap();
+    /// assert_eq!(buf, br#""\u{e9}""#);
+    /// ```
+    pub fn escape(mut self, escape: Escape) -> Self {
+        self.escape = escape;
+        self
+    }
+
+    /// Sets whether `option` `some` and `result` `ok` values are written in
+    /// the "flat" form of their payload alone, e.g. `123` for `some(123)`,
+    /// wherever the payload type allows it. Defaults to false.
+    /// ```
+    /// # use wasm_wave::{value::{Type, Value}, writer::Writer, WasmValue};
+    /// let val = Value::make_option(&Type::option(Type::STRING), Some("x".into())).unwrap();
+    /// let mut buf = vec![];
+    /// Writer::new(&mut buf).flat_payloads(true).write_value(&val).unwrap();
+    /// assert_eq!(buf, br#""x""#);
+    /// ```
+    pub fn flat_payloads(mut self, flat_payloads: bool) -> Self {
+        self.flat_payloads = flat_payloads;
+        self
+    }
+
+    /// Sets whether option-typed `none` values are omitted where they may
+    /// be: in record fields, and in trailing args written with
+    /// [`write_func_args`](Self::write_func_args). Defaults to true.
+    pub fn omit_none(mut self, omit_none: bool) -> Self {
+        self.omit_none = omit_none;
+        self
+    }
+
+    /// Sets whether lists, tuples, records and flags that don't fit within
+    /// the [`line_width`](Self::line_width) are written across multiple
+    /// lines, one element per line with a trailing comma. Defaults to false.
+    /// ```
+    /// # use wasm_wave::{value::Value, writer::Writer};
+    /// let val = Value::from([[1u8, 2, 3], [4, 5, 6]]);
+    /// let mut buf = vec![];
+    /// Writer::new(&mut buf).pretty(true).line_width(12).write_value(&val).unwrap();
+    /// assert_eq!(buf, b"[\n  [1, 2, 3],\n  [4, 5, 6],\n]");
+    /// ```
+    pub fn pretty(mut self, pretty: bool) -> Self {
+        self.pretty = pretty;
+        self
+    }
+
+    /// Sets the number of spaces each level of a multi-line value is
+    /// indented by in [`pretty`](Self::pretty) mode. Defaults to 2.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Sets the line width, in chars, that [`pretty`](Self::pretty) mode
+    /// tries to fit values within. Values that are too long to break across
+    /// lines, like long strings, may still exceed it. Defaults to 80.
+    pub fn line_width(mut self, line_width: usize) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    // Sets limits that elide parts of values, making the output unparseable.
+    pub(crate) fn limits(mut self, limits: Limits) -> Self {
+        self.limits = Some(limits);
+        self
     }
 
     /// WAVE-encodes and writes the given [`WasmValue`] to the underlying writer.
@@ -27,25 +158,29 @@ impl<W: Write> Writer<W> {
     where
         V: WasmValue,
     {
+        finish(self.write_any(val))
+    }
+
+    fn write_any<V: WasmValue>(&mut self, val: &V) -> Result<(), Stop> {
         let ty = val.ty();
         match ty.kind() {
             crate::ty::WasmTypeKind::Bool => {
                 self.write_str(if val.unwrap_bool() { "true" } else { "false" })
             }
-            crate::ty::WasmTypeKind::S8 => self.write_display(val.unwrap_s8()),
-            crate::ty::WasmTypeKind::S16 => self.write_display(val.unwrap_s16()),
-            crate::ty::WasmTypeKind::S32 => self.write_display(val.unwrap_s32()),
-            crate::ty::WasmTypeKind::S64 => self.write_display(val.unwrap_s64()),
-            crate::ty::WasmTypeKind::U8 => self.write_display(val.unwrap_u8()),
-            crate::ty::WasmTypeKind::U16 => self.write_display(val.unwrap_u16()),
-            crate::ty::WasmTypeKind::U32 => self.write_display(val.unwrap_u32()),
-            crate::ty::WasmTypeKind::U64 => self.write_display(val.unwrap_u64()),
+            crate::ty::WasmTypeKind::S8 => write!(self, "{}", val.unwrap_s8()),
+            crate::ty::WasmTypeKind::S16 => write!(self, "{}", val.unwrap_s16()),
+            crate::ty::WasmTypeKind::S32 => write!(self, "{}", val.unwrap_s32()),
+            crate::ty::WasmTypeKind::S64 => write!(self, "{}", val.unwrap_s64()),
+            crate::ty::WasmTypeKind::U8 => self.write_unsigned(val.unwrap_u8()),
+            crate::ty::WasmTypeKind::U16 => self.write_unsigned(val.unwrap_u16()),
+            crate::ty::WasmTypeKind::U32 => self.write_unsigned(val.unwrap_u32()),
+            crate::ty::WasmTypeKind::U64 => self.write_unsigned(val.unwrap_u64()),
             crate::ty::WasmTypeKind::Float32 => {
                 let f = val.unwrap_float32();
                 if f.is_nan() {
                     self.write_str("nan") // Display is "NaN"
                 } else {
-                    self.write_display(f)
+                    write!(self, "{f}")
                 }
             }
             crate::ty::WasmTypeKind::Float64 => {
@@ -53,129 +188,427 @@ impl<W: Write> Writer<W> {
                 if f.is_nan() {
                     self.write_str("nan") // Display is "NaN"
                 } else {
-                    self.write_display(f)
+                    write!(self, "{f}")
                 }
             }
             crate::ty::WasmTypeKind::Char => {
+                let ch = val.unwrap_char();
                 self.write_str("'")?;
-                self.write_char(val.unwrap_char())?;
-                self.write_str("'")
-            }
-            crate::ty::WasmTypeKind::String => {
-                self.write_str("\"")?;
-                for ch in val.unwrap_string().chars() {
-                    self.write_char(ch)?;
+                if self.is_escaped(ch) {
+                    self.write_escaped(ch)?;
+                } else {
+                    self.write_str(ch.encode_utf8(&mut [0; 4]))?;
                 }
-                self.write_str("\"")
+                self.write_str("'")
             }
+            crate::ty::WasmTypeKind::String => self.write_string(&val.unwrap_string()),
             crate::ty::WasmTypeKind::List => {
-                self.write_str("[")?;
-                for (idx, val) in val.unwrap_list().enumerate() {
-                    if idx != 0 {
-                        self.write_str(", ")?;
+                let max_len = self.limits.as_ref().map_or(usize::MAX, |l| l.max_elements);
+                let mut elements = val.unwrap_list();
+                let mut taken = 0;
+                // Yields up to `max_len` elements, then a count of the rest
+                let items = std::iter::from_fn(move || {
+                    if taken < max_len {
+                        taken += 1;
+                        elements.next().map(Ok)
+                    } else {
+                        let more = elements.by_ref().count();
+                        (more > 0).then_some(Err(more))
                     }
-                    self.write_value(&*val)?;
-                }
-                self.write_str("]")
+                });
+                self.write_seq("[", "]", items, &|w, item| match item {
+                    Ok(val) => w.write_any(&**val),
+                    Err(more) => write!(w, "…({more} more)"),
+                })
             }
             crate::ty::WasmTypeKind::Record => {
-                self.write_str("{")?;
-                let mut first = true;
-                for (name, val) in val.unwrap_record() {
-                    if !matches!(val.ty().kind(), WasmTypeKind::Option)
-                        || val.unwrap_option().is_some()
-                    {
-                        if first {
-                            first = false;
-                        } else {
-                            self.write_str(", ")?;
-                        }
-                        self.write_str(name)?;
-                        self.write_str(": ")?;
-                        self.write_value(&*val)?;
-                    }
-                }
-                self.write_str("}")
+                let omit_none = self.omit_none;
+                let fields = val
+                    .unwrap_record()
+                    .filter(|(_, val)| !(omit_none && is_none(&**val)));
+                self.write_seq("{", "}", fields, &|w, (name, val)| {
+                    w.write_label(name)?;
+                    w.write_str(": ")?;
+                    w.write_any(&**val)
+                })
             }
             crate::ty::WasmTypeKind::Tuple => {
-                self.write_str("(")?;
-                for (idx, val) in val.unwrap_tuple().enumerate() {
-                    if idx != 0 {
-                        self.write_str(", ")?;
-                    }
-                    self.write_value(&*val)?;
-                }
-                self.write_str(")")
+                self.write_seq("(", ")", val.unwrap_tuple(), &|w, val| w.write_any(&**val))
             }
             crate::ty::WasmTypeKind::Variant => {
                 let (name, val) = val.unwrap_variant();
-                self.write_str(name)?;
+                self.write_label(&name)?;
                 if let Some(val) = val {
                     self.write_str("(")?;
-                    self.write_value(&*val)?;
+                    self.write_any(&*val)?;
                     self.write_str(")")?;
                 }
                 Ok(())
             }
-            crate::ty::WasmTypeKind::Enum => self.write_str(val.unwrap_enum()),
+            crate::ty::WasmTypeKind::Enum => self.write_label(&val.unwrap_enum()),
             crate::ty::WasmTypeKind::Option => match val.unwrap_option() {
+                Some(val) if self.flat_payload(&*val) => self.write_any(&*val),
                 Some(val) => {
                     self.write_str("some(")?;
-                    self.write_value(&*val)?;
+                    self.write_any(&*val)?;
                     self.write_str(")")
                 }
                 None => self.write_str("none"),
             },
             crate::ty::WasmTypeKind::Result => {
                 let (name, val) = match val.unwrap_result() {
+                    Ok(Some(val)) if self.flat_payload(&*val) => return self.write_any(&*val),
                     Ok(val) => ("ok", val),
                     Err(val) => ("err", val),
                 };
                 self.write_str(name)?;
                 if let Some(val) = val {
                     self.write_str("(")?;
-                    self.write_value(&*val)?;
+                    self.write_any(&*val)?;
                     self.write_str(")")?;
                 }
                 Ok(())
             }
             crate::ty::WasmTypeKind::Flags => {
-                self.write_str("{")?;
-                for (idx, name) in val.unwrap_flags().enumerate() {
-                    if idx != 0 {
-                        self.write_str(", ")?;
-                    }
-                    self.write_str(name)?;
-                }
-                self.write_str("}")?;
-                Ok(())
+                self.write_seq("{", "}", val.unwrap_flags(), &|w, name| w.write_label(name))
+            }
+            crate::ty::WasmTypeKind::Own
+            | crate::ty::WasmTypeKind::Borrow
+            | crate::ty::WasmTypeKind::Stream
+            | crate::ty::WasmTypeKind::Future
+            | crate::ty::WasmTypeKind::ErrorContext => {
+                self.write_label(&crate::ty::handle_label(&ty))?;
+                write!(self, "({})", val.unwrap_handle())
             }
             crate::ty::WasmTypeKind::Unsupported => panic!("unsupported value type"),
         }
     }
 
-    fn write_str(&mut self, s: impl AsRef<str>) -> Result<(), WriterError> {
-        self.inner.write_all(s.as_ref().as_bytes())?;
+    /// WAVE-encodes and writes the given args for the given [`WasmFunc`] as
+    /// parenthesized, positional args, e.g. `(1, "a")`.
+    ///
+    /// If [`omit_none`](Self::omit_none) is set, trailing option-typed args
+    /// that are `none` are omitted, as accepted by
+    /// [`Parser::parse_params`](crate::parser::Parser::parse_params).
+    /// Returns an error if the number of args doesn't match the func.
+    /// ```
+    /// # use wasm_wave::{value::{FuncType, Type, Value}, writer::Writer, WasmValue};
+    /// let opt = Type::option(Type::U8);
+    /// let func = FuncType::new(
+    ///     [("a".into(), opt.clone()), ("b".into(), opt.clone()), ("c".into(), opt.clone())],
+    ///     [],
+    /// )
+    /// .unwrap();
+    /// let args = [Some(1u8), None, None]
+    ///     .map(|arg| Value::make_option(&opt, arg.map(Value::from)).unwrap());
+    /// let mut buf = vec![];
+    /// Writer::new(&mut buf).write_func_args(&func, &args).unwrap();
+    /// assert_eq!(buf, b"(some(1))");
+    /// ```
+    pub fn write_func_args<V, F>(&mut self, func: &F, args: &[V]) -> Result<(), WriterError>
+    where
+        V: WasmValue,
+        F: WasmFunc<Type = V::Type>,
+    {
+        let params = func.params().collect::<Vec<_>>();
+        if args.len() != params.len() {
+            return Err(WriterError::ArgCount {
+                expected: params.len(),
+                got: args.len(),
+            });
+        }
+        let mut len = args.len();
+        if self.omit_none {
+            while len > 0
+                && params[len - 1].kind() == WasmTypeKind::Option
+                && is_none(&args[len - 1])
+            {
+                len -= 1;
+            }
+        }
+        finish(self.write_seq("(", ")", args[..len].iter(), &|w, arg| w.write_any(*arg)))
+    }
+
+    // Returns true if the given `some` or `ok` payload is written flat.
+    fn flat_payload(&self, payload: &impl WasmValue) -> bool {
+        self.flat_payloads && flattenable(payload.ty().kind())
+    }
+
+    // Writes a delimited, comma-separated sequence of items with
+    // `write_item`. In pretty mode, a sequence that doesn't fit on the rest
+    // of the line is written with each item on its own line.
+    fn write_seq<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        items: impl Iterator<Item = T>,
+        write_item: &dyn Fn(&mut Self, &T) -> Result<(), Stop>,
+    ) -> Result<(), Stop> {
+        let mut items = items.peekable();
+        if let Some(limits) = &self.limits {
+            if self.nesting >= limits.max_depth && items.peek().is_some() {
+                self.write_str(open)?;
+                self.write_str("…")?;
+                return self.write_str(close);
+            }
+        }
+        self.nesting += 1;
+        let res = if self.pretty && self.measuring.is_none() {
+            self.write_seq_pretty(open, close, items.collect(), write_item)
+        } else {
+            self.write_seq_flat(open, close, items, write_item)
+        };
+        self.nesting -= 1;
+        res
+    }
+
+    fn write_seq_pretty<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        items: Vec<T>,
+        write_item: &dyn Fn(&mut Self, &T) -> Result<(), Stop>,
+    ) -> Result<(), Stop> {
+        if items.is_empty()
+            || self.fits(|w| w.write_seq_flat(open, close, items.iter(), write_item))
+        {
+            return self.write_seq_flat(open, close, items.iter(), write_item);
+        }
+        self.write_str(open)?;
+        self.depth += 1;
+        for item in &items {
+            self.write_newline()?;
+            write_item(self, item)?;
+            self.write_str(",")?;
+        }
+        self.depth -= 1;
+        self.write_newline()?;
+        self.write_str(close)
+    }
+
+    fn write_seq_flat<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        items: impl Iterator<Item = impl Borrow<T>>,
+        write_item: &dyn Fn(&mut Self, &T) -> Result<(), Stop>,
+    ) -> Result<(), Stop> {
+        self.write_str(open)?;
+        for (idx, item) in items.enumerate() {
+            if idx != 0 {
+                self.write_str(", ")?;
+            }
+            write_item(self, item.borrow())?;
+        }
+        self.write_str(close)
+    }
+
+    // Returns true if what `write` writes fits on the rest of the line.
+    fn fits(&mut self, write: impl FnOnce(&mut Self) -> Result<(), Stop>) -> bool {
+        let remaining = self.line_width.saturating_sub(self.column);
+        self.measuring = Some(remaining);
+        let fits = write(self).is_ok();
+        self.measuring = None;
+        fits
+    }
+
+    fn write_newline(&mut self) -> Result<(), Stop> {
+        let indent = self.depth * self.indent;
+        self.inner.write_str("\n")?;
+        self.inner.write_str(&" ".repeat(indent))?;
+        self.column = indent;
         Ok(())
     }
 
-    fn write_display(&mut self, d: impl std::fmt::Display) -> Result<(), WriterError> {
-        write!(self.inner, "{d}")?;
+    fn write_str(&mut self, s: &str) -> Result<(), Stop> {
+        if let Some(remaining) = &mut self.measuring {
+            // Stop measuring as soon as the line is full
+            *remaining = remaining
+                .checked_sub(s.chars().count())
+                .ok_or(Stop::LineFull)?;
+            return Ok(());
+        }
+        if let Some(max_bytes) = self.limits.as_ref().map(|l| l.max_bytes) {
+            if self.written + s.len() > max_bytes {
+                // Write as much as fits and stop writing
+                let mut end = max_bytes - self.written;
+                while !s.is_char_boundary(end) {
+                    end -= 1;
+                }
+                self.inner.write_str(&s[..end])?;
+                self.inner.write_str("…")?;
+                return Err(Stop::Truncated);
+            }
+            self.written += s.len();
+        }
+        self.inner.write_str(s)?;
+        if self.pretty {
+            self.column += s.chars().count();
+        }
         Ok(())
     }
 
-    fn write_char(&mut self, ch: char) -> Result<(), WriterError> {
+    // Writes formatted text through `write_str`; used by `write!`.
+    fn write_fmt(&mut self, args: std::fmt::Arguments) -> Result<(), Stop> {
+        struct Adapter<'a, W> {
+            writer: &'a mut Writer<W>,
+            res: Result<(), Stop>,
+        }
+        impl<W: Output> std::fmt::Write for Adapter<'_, W> {
+            fn write_str(&mut self, s: &str) -> std::fmt::Result {
+                self.res = self.writer.write_str(s);
+                self.res.as_ref().map_err(|_| std::fmt::Error).copied()
+            }
+        }
+        let mut adapter = Adapter {
+            writer: self,
+            res: Ok(()),
+        };
+        match std::fmt::write(&mut adapter, args) {
+            Ok(()) => Ok(()),
+            Err(err) => adapter.res.and(Err(Stop::Error(err.into()))),
+        }
+    }
+
+    // Writes a record field, variant or enum case, or flag label, with a `%`
+    // prefix if it would otherwise be read as a keyword.
+    fn write_label(&mut self, label: &str) -> Result<(), Stop> {
+        if !lex::is_label(label) {
+            return Err(Stop::Error(WriterError::InvalidLabel(label.to_string())));
+        }
+        if lex::KEYWORDS.contains(&label) {
+            self.write_str("%")?;
+        }
+        self.write_str(label)
+    }
+
+    fn write_unsigned(
+        &mut self,
+        n: impl std::fmt::Display + std::fmt::LowerHex,
+    ) -> Result<(), Stop> {
+        if self.hex_unsigned {
+            write!(self, "{n:#x}")
+        } else {
+            write!(self, "{n}")
+        }
+    }
+
+    // Writes a string value, writing runs of chars that aren't escaped
+    // whole.
+    fn write_string(&mut self, s: &str) -> Result<(), Stop> {
+        let max_len = self
+            .limits
+            .as_ref()
+            .map_or(usize::MAX, |l| l.max_string_len);
+        self.write_str("\"")?;
+        let mut unescaped = 0;
+        for (count, (idx, ch)) in s.char_indices().enumerate() {
+            if count == max_len {
+                self.write_str(&s[unescaped..idx])?;
+                self.write_str("\"")?;
+                let more = s[idx..].chars().count();
+                return write!(self, "…({more} more)");
+            }
+            if self.is_escaped(ch) {
+                self.write_str(&s[unescaped..idx])?;
+                self.write_escaped(ch)?;
+                unescaped = idx + ch.len_utf8();
+            }
+        }
+        self.write_str(&s[unescaped..])?;
+        self.write_str("\"")
+    }
+
+    // Returns true if the given char is escaped in char and string values.
+    fn is_escaped(&self, ch: char) -> bool {
+        // Every char that `write_escaped` escapes is escaped by `escape_debug`
+        ch.escape_debug().len() != 1 || (self.escape == Escape::Ascii && !ch.is_ascii())
+    }
+
+    fn write_escaped(&mut self, ch: char) -> Result<(), Stop> {
         if "\\\"\'\t\r\n".contains(ch) {
-            write!(self.inner, "{}", ch.escape_default())?;
-        } else if ch.is_control() {
-            write!(self.inner, "{}", ch.escape_unicode())?;
+            write!(self, "{}", ch.escape_default())
+        } else if ch.is_control() || (self.escape == Escape::Ascii && !ch.is_ascii()) {
+            write!(self, "{}", ch.escape_unicode())
         } else {
-            write!(self.inner, "{}", ch.escape_debug())?;
+            write!(self, "{}", ch.escape_debug())
         }
-        Ok(())
     }
 }
 
+/// An output that a [`Writer`] writes text to.
+///
+/// Implemented for all [`std::io::Write`]s, and for [`std::fmt::Write`]s
+/// wrapped in [`FmtWrite`].
+pub trait Output {
+    /// Writes the given string in full.
+    fn write_str(&mut self, s: &str) -> Result<(), WriterError>;
+}
+
+impl<W: std::io::Write> Output for W {
+    fn write_str(&mut self, s: &str) -> Result<(), WriterError> {
+        Ok(self.write_all(s.as_bytes())?)
+    }
+}
+
+/// Wraps a [`std::fmt::Write`], like a [`String`] or a
+/// [`std::fmt::Formatter`], as an [`Output`] for a [`Writer`].
+pub struct FmtWrite<W>(pub W);
+
+impl<W: std::fmt::Write> Output for FmtWrite<W> {
+    fn write_str(&mut self, s: &str) -> Result<(), WriterError> {
+        Ok(self.0.write_str(s)?)
+    }
+}
+
+/// Which chars in char and string values a [`Writer`] escapes.
+///
+/// Backslashes, quotes, tabs, carriage returns, and newlines are always
+/// escaped. Escaped chars are written as `\u{...}` unless they have a
+/// shorter escape, like `\n`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Escape {
+    /// Escapes control chars and the other chars that
+    /// [`char::escape_debug`] escapes, like bidirectional text controls and
+    /// combining marks. Other non-ASCII chars are written as-is.
+    #[default]
+    Printable,
+    /// Escapes all non-ASCII chars as well as those escaped by
+    /// [`Printable`](Self::Printable), so the output is pure ASCII.
+    Ascii,
+}
+
+// Why a Writer stopped writing before the end of a value.
+enum Stop {
+    // While measuring, the rest of the line was filled
+    LineFull,
+    // `Limits::max_bytes` was reached
+    Truncated,
+    Error(WriterError),
+}
+
+impl From<WriterError> for Stop {
+    fn from(err: WriterError) -> Self {
+        Self::Error(err)
+    }
+}
+
+// Returns the result of a complete write, which succeeds if it stopped at
+// `Limits::max_bytes`.
+fn finish(res: Result<(), Stop>) -> Result<(), WriterError> {
+    match res {
+        Ok(()) | Err(Stop::Truncated) => Ok(()),
+        Err(Stop::Error(err)) => Err(err),
+        Err(Stop::LineFull) => unreachable!("lines are only measured within `fits`"),
+    }
+}
+
+// Returns true if the given value is an option `none`.
+fn is_none(val: &impl WasmValue) -> bool {
+    val.ty().kind() == WasmTypeKind::Option && val.unwrap_option().is_none()
+}
+
 impl<W> AsMut<W> for Writer<W> {
     fn as_mut(&mut self) -> &mut W {
         &mut self.inner
@@ -189,4 +622,19 @@ pub enum WriterError {
     /// An error from the underlying writer
     #[error("write failed: {0}")]
     Io(#[from] std::io::Error),
+    /// An error from the underlying [`std::fmt::Write`]
+    #[error("write failed: {0}")]
+    Fmt(#[from] std::fmt::Error),
+    /// A record field, variant or enum case, or flag label that isn't valid
+    /// kebab-case
+    #[error("invalid label `{0}`")]
+    InvalidLabel(String),
+    /// A number of func args that doesn't match the func's params
+    #[error("wrong number of args; expected {expected}, got {got}")]
+    ArgCount {
+        /// The number of func params
+        expected: usize,
+        /// The number of args given
+        got: usize,
+    },
 }